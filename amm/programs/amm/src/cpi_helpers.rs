@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+
+// Helpers for programs calling the AMM through CPI (build with the `cpi`
+// feature). The PDA helpers mirror the seeds the instructions check, and
+// `PoolAccounts` fills in the pool side of the generated `cpi::accounts`
+// structs so callers only pass their own accounts. Optional accounts (oracle,
+// allowlist, referrer, temporary wSOL) are left out - pools that need them
+// have to be called with the structs filled in by hand. The LP fee checkpoint
+// is the exception, since it belongs to the caller rather than the pool.
+
+fn pool_address(prefix: &[u8], token_a_mint: &Pubkey, token_b_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[prefix, token_a_mint.as_ref(), token_b_mint.as_ref()],
+        &crate::ID,
+    )
+}
+
+pub fn amm_address(token_a_mint: &Pubkey, token_b_mint: &Pubkey) -> (Pubkey, u8) {
+    pool_address(b"amm", token_a_mint, token_b_mint)
+}
+
+pub fn reserve_a_address(token_a_mint: &Pubkey, token_b_mint: &Pubkey) -> (Pubkey, u8) {
+    pool_address(b"reserve_a", token_a_mint, token_b_mint)
+}
+
+pub fn reserve_b_address(token_a_mint: &Pubkey, token_b_mint: &Pubkey) -> (Pubkey, u8) {
+    pool_address(b"reserve_b", token_a_mint, token_b_mint)
+}
+
+pub fn pool_authority_address(token_a_mint: &Pubkey, token_b_mint: &Pubkey) -> (Pubkey, u8) {
+    pool_address(b"authority", token_a_mint, token_b_mint)
+}
+
+pub fn lp_mint_address(token_a_mint: &Pubkey, token_b_mint: &Pubkey) -> (Pubkey, u8) {
+    pool_address(b"lp_mint", token_a_mint, token_b_mint)
+}
+
+pub fn lp_fee_checkpoint_address(amm: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"lp_fees", amm.as_ref(), owner.as_ref()], &crate::ID)
+}
+
+// The pool accounts shared by swap, deposit and withdraw
+#[cfg(feature = "cpi")]
+#[derive(Clone)]
+pub struct PoolAccounts<'info> {
+    pub amm: AccountInfo<'info>,
+    pub reserve_a: AccountInfo<'info>,
+    pub reserve_b: AccountInfo<'info>,
+    pub pool_authority: AccountInfo<'info>,
+    pub token_a_mint: AccountInfo<'info>,
+    pub token_b_mint: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+#[cfg(feature = "cpi")]
+impl<'info> PoolAccounts<'info> {
+    pub fn swap(
+        &self,
+        token_a_account: AccountInfo<'info>,
+        token_b_account: AccountInfo<'info>,
+        signer: AccountInfo<'info>,
+    ) -> crate::cpi::accounts::Swap<'info> {
+        crate::cpi::accounts::Swap {
+            amm: self.amm.clone(),
+            token_a_account,
+            token_b_account,
+            reserve_a: self.reserve_a.clone(),
+            reserve_b: self.reserve_b.clone(),
+            pool_authority: self.pool_authority.clone(),
+            token_a_mint: self.token_a_mint.clone(),
+            token_b_mint: self.token_b_mint.clone(),
+            oracle: None,
+            referrer_token_account: None,
+            referrer: None,
+            config: None,
+            allowlist_entry: None,
+            allowlist_authority: None,
+            temp_wsol_account: None,
+            token_program: self.token_program.clone(),
+            associated_token_program: self.associated_token_program.clone(),
+            system_program: self.system_program.clone(),
+            signer,
+        }
+    }
+
+    pub fn deposit(
+        &self,
+        token_a_account: AccountInfo<'info>,
+        token_b_account: AccountInfo<'info>,
+        token_lp_account: AccountInfo<'info>,
+        lp_fee_checkpoint: Option<AccountInfo<'info>>,
+        lp_mint: AccountInfo<'info>,
+        signer: AccountInfo<'info>,
+    ) -> crate::cpi::accounts::Deposit<'info> {
+        crate::cpi::accounts::Deposit {
+            amm: self.amm.clone(),
+            token_a_account,
+            token_b_account,
+            token_lp_account,
+            lp_fee_checkpoint,
+            reserve_a: self.reserve_a.clone(),
+            reserve_b: self.reserve_b.clone(),
+            pool_authority: self.pool_authority.clone(),
+            token_a_mint: self.token_a_mint.clone(),
+            token_b_mint: self.token_b_mint.clone(),
+            lp_mint,
+            allowlist_entry: None,
+            allowlist_authority: None,
+            temp_wsol_account: None,
+            token_program: self.token_program.clone(),
+            associated_token_program: self.associated_token_program.clone(),
+            system_program: self.system_program.clone(),
+            signer,
+        }
+    }
+
+    pub fn withdraw(
+        &self,
+        token_a_account: AccountInfo<'info>,
+        token_b_account: AccountInfo<'info>,
+        token_lp_account: AccountInfo<'info>,
+        lp_fee_checkpoint: Option<AccountInfo<'info>>,
+        lp_mint: AccountInfo<'info>,
+        signer: AccountInfo<'info>,
+    ) -> crate::cpi::accounts::Withdraw<'info> {
+        crate::cpi::accounts::Withdraw {
+            amm: self.amm.clone(),
+            token_a_account,
+            token_b_account,
+            token_lp_account,
+            lp_fee_checkpoint,
+            reserve_a: self.reserve_a.clone(),
+            reserve_b: self.reserve_b.clone(),
+            pool_authority: self.pool_authority.clone(),
+            token_a_mint: self.token_a_mint.clone(),
+            token_b_mint: self.token_b_mint.clone(),
+            lp_mint,
+            allowlist_entry: None,
+            allowlist_authority: None,
+            temp_wsol_account: None,
+            token_program: self.token_program.clone(),
+            associated_token_program: self.associated_token_program.clone(),
+            system_program: self.system_program.clone(),
+            signer,
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::states::{AMMError, AllowlistEntry, AllowlistEntryAdded, AMM};
+
+#[derive(Accounts)]
+pub struct AddAllowlistEntry<'info> {
+    #[account(
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        has_one = allowlist_authority @ AMMError::Unauthorized,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        init,
+        payer = allowlist_authority,
+        space = 8 + AllowlistEntry::INIT_SPACE,
+        seeds = [b"allowlist", amm.key().as_ref(), wallet.key().as_ref()],
+        bump,
+    )]
+    pub allowlist_entry: Box<Account<'info, AllowlistEntry>>,
+
+    /// CHECK: the wallet being allowlisted
+    pub wallet: UncheckedAccount<'info>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub allowlist_authority: Signer<'info>,
+}
+
+impl<'info> AddAllowlistEntry<'info> {
+    pub fn add_allowlist_entry(&mut self, bumps: &AddAllowlistEntryBumps) -> Result<()> {
+        self.allowlist_entry.set_inner(AllowlistEntry {
+            amm: self.amm.key(),
+            wallet: self.wallet.key(),
+            added_at: Clock::get()?.unix_timestamp,
+            bump: bumps.allowlist_entry,
+        });
+
+        emit!(AllowlistEntryAdded {
+            amm: self.amm.key(),
+            wallet: self.wallet.key(),
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::{
+    instructions::farm::update_farm_rewards,
+    states::{AMMError, Farm, FarmReward, FARM_MAX_REWARDS},
+};
+
+#[derive(Accounts)]
+pub struct AddFarmReward<'info> {
+    #[account(
+        mut,
+        seeds = [b"farm", farm.amm.as_ref()],
+        bump = farm.bump,
+        has_one = authority @ AMMError::Unauthorized,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"farm_reward_vault", farm.key().as_ref(), reward_mint.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = farm
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    pub reward_mint: Box<Account<'info, Mint>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+impl<'info> AddFarmReward<'info> {
+    pub fn add_farm_reward(&mut self) -> Result<()> {
+        let index = self.farm.reward_count as usize;
+        require!(index < FARM_MAX_REWARDS, AMMError::FarmFull);
+
+        let now = Clock::get()?.unix_timestamp;
+        update_farm_rewards(&mut self.farm, now)?;
+
+        // emissions start once the reward is funded
+        self.farm.rewards[index] = FarmReward {
+            mint: self.reward_mint.key(),
+            vault: self.reward_vault.key(),
+            emissions_per_second: 0,
+            reward_per_share: 0,
+            last_update_ts: now,
+            end_ts: now,
+        };
+        self.farm.reward_count += 1;
+
+        Ok(())
+    }
+}
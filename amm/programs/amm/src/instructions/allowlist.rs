@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+use crate::states::{AMMError, AllowlistEntry, AMM};
+
+// Permissioned pools accept a signer with an allowlist entry, or any trade
+// co-signed by the pool's allowlist authority. The entry's seeds are checked by
+// the instructions' account constraints.
+pub fn check_allowlist(
+    amm: &AMM,
+    allowlist_entry: Option<&Account<AllowlistEntry>>,
+    allowlist_authority: Option<&Signer>,
+) -> Result<()> {
+    if amm.allowlist_authority == Pubkey::default() || allowlist_entry.is_some() {
+        return Ok(());
+    }
+
+    require!(
+        allowlist_authority.is_some_and(|authority| authority.key() == amm.allowlist_authority),
+        AMMError::NotAllowlisted
+    );
+
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{close_account, transfer, CloseAccount, TokenAccount, Transfer};
+
+use crate::{
+    instructions::{
+        dynamic_fee::amount_after_fee,
+        utils::{swap_output, to_u64},
+    },
+    states::{AMMError, Batch, SwapIntent},
+};
+
+// BATCH CLEARING
+// Intents selling A (inA in total) and selling B (inB) are matched against each
+// other at one price p, and only the imbalance trades against the curve. For
+// the price of that net trade to be p as well, with A as the net side:
+// p = rB / (rA + dA)  and  dA = inA - inB / p
+// dA = (inA X rB - inB X rA) / (rB + inB)
+// The net trade then pays the swap fee like any other trade, so the net side
+// ends up slightly below p. Returns (outA, outB) - the A paid out to the
+// intents selling B and the B paid out to the intents selling A.
+pub fn batch_clearing(
+    in_a: u64,
+    in_b: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+    fee_bps: u16,
+) -> Result<(u64, u64)> {
+    let value_a = in_a as u128 * reserve_b as u128;
+    let value_b = in_b as u128 * reserve_a as u128;
+
+    if value_a >= value_b {
+        let net_a = to_u64((value_a - value_b) / (reserve_b as u128 + in_b as u128))?;
+        let net_out = swap_output(amount_after_fee(net_a, fee_bps)?, reserve_a, reserve_b)?;
+        let out_b = in_b
+            .checked_add(net_out)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+
+        Ok((in_a - net_a, out_b))
+    } else {
+        let net_b = to_u64((value_b - value_a) / (reserve_a as u128 + in_a as u128))?;
+        let net_out = swap_output(amount_after_fee(net_b, fee_bps)?, reserve_b, reserve_a)?;
+        let out_a = in_a
+            .checked_add(net_out)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+
+        Ok((out_a, in_b - net_b))
+    }
+}
+
+// intent's share of its side's output = amountIn X outTotal / inTotal
+pub fn batch_payout(amount_in: u64, total_in: u64, total_out: u64) -> Result<u64> {
+    if total_in == 0 {
+        return Ok(0);
+    }
+
+    to_u64(amount_in as u128 * total_out as u128 / total_in as u128)
+}
+
+// Starts a settlement pass, pricing the intents still in at the given
+// (effective) reserves
+pub fn start_pass(batch: &mut Batch, reserve_a: u64, reserve_b: u64) -> Result<()> {
+    let (out_a, out_b) = batch_clearing(
+        batch.total_in_a,
+        batch.total_in_b,
+        reserve_a,
+        reserve_b,
+        batch.fee_bps,
+    )?;
+
+    batch.pass += 1;
+    batch.pass_in_a = batch.total_in_a;
+    batch.pass_in_b = batch.total_in_b;
+    batch.total_out_a = out_a;
+    batch.total_out_b = out_b;
+    batch.pass_intents = batch.sell_a_count + batch.sell_b_count;
+    batch.checked_count = 0;
+    batch.excluded_in_pass = 0;
+
+    Ok(())
+}
+
+// Checks the intents of a page that are still in, and not yet checked this
+// pass, against the pass price. Those whose min_amount_out it doesn't meet
+// are excluded and taken out of the batch totals.
+pub fn check_intents(batch: &mut Batch, intents: &mut [SwapIntent]) -> Result<()> {
+    for intent in intents.iter_mut() {
+        if intent.excluded || intent.checked_pass == batch.pass {
+            continue;
+        }
+        intent.checked_pass = batch.pass;
+        batch.checked_count += 1;
+
+        let payout = if intent.sell_a {
+            batch_payout(intent.amount_in, batch.pass_in_a, batch.total_out_b)?
+        } else {
+            batch_payout(intent.amount_in, batch.pass_in_b, batch.total_out_a)?
+        };
+        if payout >= intent.min_amount_out {
+            continue;
+        }
+
+        intent.excluded = true;
+        batch.excluded_in_pass += 1;
+        batch.excluded_count += 1;
+        if intent.sell_a {
+            batch.total_in_a -= intent.amount_in;
+            batch.sell_a_count -= 1;
+        } else {
+            batch.total_in_b -= intent.amount_in;
+            batch.sell_b_count -= 1;
+        }
+    }
+
+    Ok(())
+}
+
+// Closes the batch vaults and the batch itself once nothing is left to claim,
+// returning their rent to the payer. Settlement leaves each vault holding
+// exactly what its intents are owed, so anything still in a vault was sent to
+// it directly - it goes to the pool's reserve in the same token, like any
+// other donation, so it can't keep the vault open.
+pub fn close_batch<'info>(
+    batch: &mut Account<'info, Batch>,
+    vaults: [&mut Account<'info, TokenAccount>; 2],
+    reserves: [AccountInfo<'info>; 2],
+    payer: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let amm_key = batch.amm;
+    let start_slot = batch.start_slot.to_le_bytes();
+    let seeds: &[&[u8]; 4] = &[b"batch", amm_key.as_ref(), &start_slot, &[batch.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    for (vault, reserve) in vaults.into_iter().zip(reserves) {
+        vault.reload()?;
+        if vault.amount > 0 {
+            let transfer_to_reserve_ctx = CpiContext::new_with_signer(
+                token_program.clone(),
+                Transfer {
+                    from: vault.to_account_info(),
+                    to: reserve,
+                    authority: batch.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            transfer(transfer_to_reserve_ctx, vault.amount)?;
+        }
+
+        let close_vault_ctx = CpiContext::new_with_signer(
+            token_program.clone(),
+            CloseAccount {
+                account: vault.to_account_info(),
+                destination: payer.clone(),
+                authority: batch.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        close_account(close_vault_ctx)?;
+    }
+
+    batch.close(payer.clone())
+}
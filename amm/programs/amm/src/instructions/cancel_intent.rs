@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Token, TokenAccount, Transfer};
+
+use crate::states::{AMMError, Batch, SwapIntent};
+
+// Intents can be cancelled while their batch window is still open. Once it has
+// ended the intent is committed to settlement, so nobody can back out after
+// seeing how the rest of the batch lines up.
+
+#[derive(Accounts)]
+pub struct CancelIntent<'info> {
+    #[account(
+        mut,
+        seeds = [b"batch", batch.amm.as_ref(), &batch.start_slot.to_le_bytes()],
+        bump = batch.bump,
+    )]
+    pub batch: Box<Account<'info, Batch>>,
+
+    #[account(
+        mut,
+        seeds = [b"swap_intent", batch.key().as_ref(), owner.key().as_ref()],
+        bump = intent.bump,
+        has_one = batch,
+        has_one = owner @ AMMError::Unauthorized,
+        close = owner,
+    )]
+    pub intent: Box<Account<'info, SwapIntent>>,
+
+    #[account(
+        mut,
+        address = if intent.sell_a {
+            batch.vault_a
+        } else {
+            batch.vault_b
+        } @ AMMError::InvalidIntent,
+    )]
+    pub batch_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = batch_vault.mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+impl<'info> CancelIntent<'info> {
+    pub fn cancel_intent(&mut self) -> Result<()> {
+        require!(
+            Clock::get()?.slot < self.batch.end_slot,
+            AMMError::BatchClosed
+        );
+
+        let amount_in = self.intent.amount_in;
+        let batch = &mut self.batch;
+        if self.intent.sell_a {
+            batch.total_in_a -= amount_in;
+            batch.sell_a_count -= 1;
+        } else {
+            batch.total_in_b -= amount_in;
+            batch.sell_b_count -= 1;
+        }
+        batch.intent_count -= 1;
+
+        let amm_key = batch.amm;
+        let start_slot = batch.start_slot.to_le_bytes();
+        let seeds: &[&[u8]; 4] = &[b"batch", amm_key.as_ref(), &start_slot, &[batch.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        let refund_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.batch_vault.to_account_info(),
+                to: self.owner_token_account.to_account_info(),
+                authority: self.batch.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(refund_ctx, amount_in)
+    }
+}
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{close_account, transfer, CloseAccount, Token, TokenAccount, Transfer};
+
+use crate::states::{AMMError, LimitOrder};
+
+#[derive(Accounts)]
+pub struct CancelLimitOrder<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"limit_order",
+            order.amm.as_ref(),
+            owner.key().as_ref(),
+            &order.order_id.to_le_bytes()
+        ],
+        bump = order.bump,
+        has_one = owner @ AMMError::Unauthorized,
+        close = owner,
+    )]
+    pub order: Box<Account<'info, LimitOrder>>,
+
+    #[account(
+        mut,
+        address = order.vault,
+    )]
+    pub order_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = order_vault.mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+impl<'info> CancelLimitOrder<'info> {
+    pub fn cancel_limit_order(&mut self) -> Result<()> {
+        let amm_key = self.order.amm;
+        let owner_key = self.owner.key();
+        let order_id = self.order.order_id.to_le_bytes();
+        let seeds: &[&[u8]; 5] = &[
+            b"limit_order",
+            amm_key.as_ref(),
+            owner_key.as_ref(),
+            &order_id,
+            &[self.order.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let refund_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.order_vault.to_account_info(),
+                to: self.owner_token_account.to_account_info(),
+                authority: self.order.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(refund_ctx, self.order_vault.amount)?;
+
+        let close_vault_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.order_vault.to_account_info(),
+                destination: self.owner.to_account_info(),
+                authority: self.order.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        close_account(close_vault_ctx)
+    }
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    instructions::dynamic_fee::{pool_price, price_move_bps},
+    states::{AMMError, AMM, BPS_DENOMINATOR},
+};
+
+// CIRCUIT BREAKER
+// The first trade after a window of breaker_window_slots has elapsed opens a
+// new window at the pre-trade price and reserves. Within a window, trades are
+// rejected once they would move the price more than max_price_change_bps away
+// from the window's start price, or pay out more than max_outflow_bps of a
+// reserve in total. A rejected trade changes nothing, so the pool keeps
+// trading in the other direction and fully resumes when the window rolls over.
+
+// Checks a trade priced against (reserve_a, reserve_b) that leaves the pool at
+// (reserve_a_after, reserve_b_after) and pays out (outflow_a, outflow_b)
+pub fn check_circuit_breaker(
+    amm: &mut AMM,
+    reserve_a: u64,
+    reserve_b: u64,
+    reserve_a_after: u64,
+    reserve_b_after: u64,
+    (outflow_a, outflow_b): (u64, u64),
+    slot: u64,
+) -> Result<()> {
+    if amm.breaker_window_slots == 0 {
+        return Ok(());
+    }
+
+    // a zero start price means no window has been opened yet
+    let window_end = amm
+        .window_start_slot
+        .saturating_add(amm.breaker_window_slots);
+    if amm.window_start_price == 0 || slot >= window_end {
+        amm.window_start_slot = slot;
+        amm.window_start_price = pool_price(reserve_a, reserve_b);
+        amm.window_start_reserve_a = reserve_a;
+        amm.window_start_reserve_b = reserve_b;
+        amm.window_outflow_a = 0;
+        amm.window_outflow_b = 0;
+    }
+
+    if amm.max_price_change_bps > 0 {
+        let price_change = price_move_bps(
+            amm.window_start_price,
+            pool_price(reserve_a_after, reserve_b_after),
+        );
+        require!(
+            price_change <= amm.max_price_change_bps as u64,
+            AMMError::CircuitBreakerTripped
+        );
+    }
+
+    amm.window_outflow_a = amm.window_outflow_a.saturating_add(outflow_a);
+    amm.window_outflow_b = amm.window_outflow_b.saturating_add(outflow_b);
+
+    if amm.max_outflow_bps > 0 {
+        // outflow X 10000 <= startReserve X maxOutflow
+        let within_limit = |outflow: u64, start_reserve: u64| {
+            outflow as u128 * BPS_DENOMINATOR as u128
+                <= start_reserve as u128 * amm.max_outflow_bps as u128
+        };
+        require!(
+            within_limit(amm.window_outflow_a, amm.window_start_reserve_a)
+                && within_limit(amm.window_outflow_b, amm.window_start_reserve_b),
+            AMMError::CircuitBreakerTripped
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Token, TokenAccount, Transfer};
+
+use crate::{
+    instructions::batch::{batch_payout, close_batch},
+    states::{AMMError, Batch, SwapIntent, AMM},
+};
+
+// Anyone can pay out a settled intent to its owner - its share of the batch
+// output, or a refund of the input if settlement excluded it. The last intent
+// claimed on each side gets that side's rounding dust, so the vaults end up
+// empty and the last claim closes them along with the batch.
+
+#[derive(Accounts)]
+pub struct ClaimIntent<'info> {
+    #[account(
+        mut,
+        seeds = [b"batch", batch.amm.as_ref(), &batch.start_slot.to_le_bytes()],
+        bump = batch.bump,
+    )]
+    pub batch: Box<Account<'info, Batch>>,
+
+    #[account(
+        mut,
+        seeds = [b"swap_intent", batch.key().as_ref(), owner.key().as_ref()],
+        bump = intent.bump,
+        has_one = batch,
+        has_one = owner,
+        close = owner,
+    )]
+    pub intent: Box<Account<'info, SwapIntent>>,
+
+    #[account(
+        mut,
+        address = batch.vault_a,
+    )]
+    pub batch_vault_a: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        address = batch.vault_b,
+    )]
+    pub batch_vault_b: Box<Account<'info, TokenAccount>>,
+
+    // the last claim sends anything left in the vaults to the reserves
+    #[account(address = batch.amm)]
+    pub amm: AccountLoader<'info, AMM>,
+    #[account(
+        mut,
+        address = amm.load()?.reserve_a,
+    )]
+    pub reserve_a: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        address = amm.load()?.reserve_b,
+    )]
+    pub reserve_b: Box<Account<'info, TokenAccount>>,
+
+    // in the output token, or the input token for an excluded intent
+    #[account(
+        mut,
+        token::authority = owner
+    )]
+    pub owner_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: receives the intent's rent, checked against the intent
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: gets the batch and vault rent back after the last claim
+    #[account(mut, address = batch.payer)]
+    pub payer: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> ClaimIntent<'info> {
+    pub fn claim_intent(&mut self) -> Result<()> {
+        let batch = &mut self.batch;
+        let intent = &self.intent;
+        require!(batch.settled, AMMError::BatchNotReady);
+
+        let amount = if intent.excluded {
+            intent.amount_in
+        } else if intent.sell_a {
+            batch.sell_a_count -= 1;
+            let amount = if batch.sell_a_count == 0 {
+                batch.total_out_b - batch.paid_out_b
+            } else {
+                batch_payout(intent.amount_in, batch.total_in_a, batch.total_out_b)?
+            };
+            batch.paid_out_b += amount;
+            amount
+        } else {
+            batch.sell_b_count -= 1;
+            let amount = if batch.sell_b_count == 0 {
+                batch.total_out_a - batch.paid_out_a
+            } else {
+                batch_payout(intent.amount_in, batch.total_in_b, batch.total_out_a)?
+            };
+            batch.paid_out_a += amount;
+            amount
+        };
+        batch.intent_count -= 1;
+
+        let batch_vault = if intent.sell_a == intent.excluded {
+            &self.batch_vault_a
+        } else {
+            &self.batch_vault_b
+        };
+        require_keys_eq!(
+            self.owner_token_account.mint,
+            batch_vault.mint,
+            AMMError::InvalidIntent
+        );
+
+        let amm_key = batch.amm;
+        let start_slot = batch.start_slot.to_le_bytes();
+        let seeds: &[&[u8]; 4] = &[b"batch", amm_key.as_ref(), &start_slot, &[batch.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        let payout_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: batch_vault.to_account_info(),
+                to: self.owner_token_account.to_account_info(),
+                authority: self.batch.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(payout_ctx, amount)?;
+
+        if self.batch.intent_count == 0 {
+            close_batch(
+                &mut self.batch,
+                [&mut self.batch_vault_a, &mut self.batch_vault_b],
+                [
+                    self.reserve_a.to_account_info(),
+                    self.reserve_b.to_account_info(),
+                ],
+                &self.payer.to_account_info(),
+                &self.token_program.to_account_info(),
+            )?;
+        }
+
+        Ok(())
+    }
+}
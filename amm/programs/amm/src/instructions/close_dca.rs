@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{close_account, transfer, CloseAccount, Token, TokenAccount, Transfer};
+
+use crate::states::{AMMError, DcaOrder};
+
+// Pays out the proceeds and any unsold input, whether or not every tranche ran
+
+#[derive(Accounts)]
+pub struct CloseDca<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"dca",
+            dca.amm.as_ref(),
+            owner.key().as_ref(),
+            &dca.dca_id.to_le_bytes()
+        ],
+        bump = dca.bump,
+        has_one = owner @ AMMError::Unauthorized,
+        has_one = input_vault,
+        has_one = output_vault,
+        close = owner,
+    )]
+    pub dca: Box<Account<'info, DcaOrder>>,
+
+    #[account(mut)]
+    pub input_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub output_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = input_vault.mint,
+        token::authority = owner
+    )]
+    pub owner_input_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = output_vault.mint,
+        token::authority = owner
+    )]
+    pub owner_output_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+impl<'info> CloseDca<'info> {
+    pub fn close_dca(&mut self) -> Result<()> {
+        let amm_key = self.dca.amm;
+        let owner_key = self.owner.key();
+        let dca_id = self.dca.dca_id.to_le_bytes();
+        let seeds: &[&[u8]; 5] = &[
+            b"dca",
+            amm_key.as_ref(),
+            owner_key.as_ref(),
+            &dca_id,
+            &[self.dca.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        for (vault, owner_account) in [
+            (&self.input_vault, &self.owner_input_account),
+            (&self.output_vault, &self.owner_output_account),
+        ] {
+            let payout_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: vault.to_account_info(),
+                    to: owner_account.to_account_info(),
+                    authority: self.dca.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            transfer(payout_ctx, vault.amount)?;
+
+            let close_vault_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                CloseAccount {
+                    account: vault.to_account_info(),
+                    destination: self.owner.to_account_info(),
+                    authority: self.dca.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            close_account(close_vault_ctx)?;
+        }
+
+        Ok(())
+    }
+}
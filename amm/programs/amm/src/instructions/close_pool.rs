@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{
+    close_account, transfer, CloseAccount, Mint, Token, TokenAccount, Transfer,
+};
+
+use crate::states::{AMMError, AMM, LAUNCH_ACTIVE};
+
+// The lp_mint is left allocated - legacy SPL Token mints cannot be closed.
+// `initialize` reuses it, with the decimals it was created with, if the same
+// pair is created again.
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    #[account(
+        mut,
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        has_one = creator @ AMMError::Unauthorized,
+        close = creator,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve_a", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = pool_authority
+    )]
+    pub reserve_a: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"reserve_b", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = pool_authority
+    )]
+    pub reserve_b: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: pool authority over token reserves and lp mint
+    #[account(
+        seeds=[b"authority", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    // rounding dust left in the reserves is swept here before they are closed
+    #[account(
+        mut,
+        token::mint = token_a_mint,
+        token::authority = creator
+    )]
+    pub creator_token_a_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = token_b_mint,
+        token::authority = creator
+    )]
+    pub creator_token_b_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+}
+
+impl<'info> ClosePool<'info> {
+    pub fn close_pool(&mut self, bumps: &ClosePoolBumps) -> Result<()> {
+        let amm = self.amm.load()?;
+        require!(amm.lp_supply == 0, AMMError::PoolNotEmpty);
+        // the launch pool graduates into this pool, which has to stay open until then
+        require!(amm.launch_state != LAUNCH_ACTIVE, AMMError::LaunchActive);
+        drop(amm);
+
+        let token_a_mint_key = self.token_a_mint.key();
+        let token_b_mint_key = self.token_b_mint.key();
+
+        let seeds: &[&[u8]; 4] = &[
+            b"authority",
+            token_a_mint_key.as_ref(),
+            token_b_mint_key.as_ref(),
+            &[bumps.pool_authority],
+        ];
+
+        let signer_seeds = &[&seeds[..]];
+
+        for (reserve, creator_token_account) in [
+            (&self.reserve_a, &self.creator_token_a_account),
+            (&self.reserve_b, &self.creator_token_b_account),
+        ] {
+            if reserve.amount > 0 {
+                let sweep_dust_ctx = CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    Transfer {
+                        from: reserve.to_account_info(),
+                        to: creator_token_account.to_account_info(),
+                        authority: self.pool_authority.to_account_info(),
+                    },
+                    signer_seeds,
+                );
+
+                transfer(sweep_dust_ctx, reserve.amount)?;
+            }
+
+            let close_reserve_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                CloseAccount {
+                    account: reserve.to_account_info(),
+                    destination: self.creator.to_account_info(),
+                    authority: self.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            close_account(close_reserve_ctx)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::states::{LpFeeCheckpoint, AMM};
+
+// Opts a wallet in to tracking the fees its LP tokens earn. Only LP tokens
+// minted by deposits made with the checkpoint count, so it is created before
+// the deposits it should cover.
+
+#[derive(Accounts)]
+pub struct CreateLpFeeCheckpoint<'info> {
+    #[account(
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + LpFeeCheckpoint::INIT_SPACE,
+        seeds = [b"lp_fees", amm.key().as_ref(), owner.key().as_ref()],
+        bump,
+    )]
+    pub lp_fee_checkpoint: Box<Account<'info, LpFeeCheckpoint>>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+impl<'info> CreateLpFeeCheckpoint<'info> {
+    pub fn create_lp_fee_checkpoint(&mut self, bumps: &CreateLpFeeCheckpointBumps) -> Result<()> {
+        let amm = self.amm.load()?;
+        let checkpoint = &mut self.lp_fee_checkpoint;
+        checkpoint.amm = self.amm.key();
+        checkpoint.owner = self.owner.key();
+        checkpoint.fee_growth_a = amm.fee_growth_a;
+        checkpoint.fee_growth_b = amm.fee_growth_b;
+        checkpoint.bump = bumps.lp_fee_checkpoint;
+
+        Ok(())
+    }
+}
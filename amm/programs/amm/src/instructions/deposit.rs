@@ -1,7 +1,18 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{mint_to, transfer, Mint, MintTo, Token, TokenAccount, Transfer};
-
-use crate::states::{AMMError, AMM};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{mint_to, transfer, Mint, MintTo, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    instructions::{
+        allowlist::check_allowlist,
+        lp_fees::settle_lp_fees,
+        native_sol::{close_temp_account, input_account, is_native_a, wrap_sol},
+        utils::{initial_lp_tokens, is_proportional_deposit, proportional_lp_tokens},
+    },
+    states::{AMMError, AllowlistEntry, LpFeeCheckpoint, AMM, LAUNCH_ACTIVE},
+};
 
 #[derive(Accounts)]
 pub struct Deposit<'info> {
@@ -27,12 +38,21 @@ pub struct Deposit<'info> {
     pub token_b_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
-        mut,
+        init_if_needed,
+        payer = signer,
         associated_token::mint = lp_mint,
         associated_token::authority = signer
     )]
     pub token_lp_account: Box<Account<'info, TokenAccount>>,
 
+    // opt-in fee tracking, see create_lp_fee_checkpoint
+    #[account(
+        mut,
+        seeds = [b"lp_fees", amm.key().as_ref(), signer.key().as_ref()],
+        bump = lp_fee_checkpoint.bump,
+    )]
+    pub lp_fee_checkpoint: Option<Box<Account<'info, LpFeeCheckpoint>>>,
+
     #[account(
         mut,
         seeds = [b"reserve_a", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
@@ -67,8 +87,22 @@ pub struct Deposit<'info> {
     )]
     pub lp_mint: Box<Account<'info, Mint>>,
 
+    // permissioned pools need one of these
+    #[account(
+        seeds = [b"allowlist", amm.key().as_ref(), signer.key().as_ref()],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Option<Box<Account<'info, AllowlistEntry>>>,
+    pub allowlist_authority: Option<Signer<'info>>,
+
+    // temporary account the `_sol` variant wraps its wSOL side into
+    #[account(mut, seeds = [b"wsol", signer.key().as_ref()], bump)]
+    /// CHECK: created by wrap_sol and closed within the instruction
+    pub temp_wsol_account: Option<UncheckedAccount<'info>>,
+
     pub token_program: Program<'info, Token>,
-    // pub associated_token_program: Program<'info, AssociatedToken>,  needed if init
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
     #[account(mut)]
     pub signer: Signer<'info>,
 }
@@ -80,11 +114,17 @@ impl<'info> Deposit<'info> {
         quantity_b: u64,
         bumps: &DepositBumps,
     ) -> Result<()> {
+        check_allowlist(
+            &*self.amm.load()?,
+            self.allowlist_entry.as_deref(),
+            self.allowlist_authority.as_ref(),
+        )?;
+
         require!(quantity_a > 0 && quantity_b > 0, AMMError::InvalidQuantity);
 
         let amm = self.amm.load()?;
-        let tokens_to_issue: u64;
-        if amm.lp_supply == 0 {
+        require!(amm.launch_state != LAUNCH_ACTIVE, AMMError::LaunchActive);
+        let tokens_to_issue = if amm.lp_supply == 0 {
             // sqrt mean of token deposits
             // first LP sets constant product
             // LP[minted] = Sqrt(qA X qB)
@@ -94,11 +134,23 @@ impl<'info> Deposit<'info> {
             // LP[minted] = Sqrt(100 X 400) = 200
             // First LP gets 200 tokens
 
-            let value = (quantity_a as u128) * (quantity_b as u128);
-            tokens_to_issue = binary_search_sqrt(value);
+            // amounts are scaled by decimals so that 200 is in whole LP tokens
+
+            initial_lp_tokens(
+                quantity_a,
+                quantity_b,
+                self.token_a_mint.decimals,
+                self.token_b_mint.decimals,
+                amm.lp_decimals,
+            )?
         } else {
             require!(
-                (quantity_a * self.reserve_b.amount == quantity_b * self.reserve_a.amount),
+                is_proportional_deposit(
+                    quantity_a,
+                    quantity_b,
+                    self.reserve_a.amount,
+                    self.reserve_b.amount
+                ),
                 AMMError::InvalidLiquidity
             );
 
@@ -109,28 +161,32 @@ impl<'info> Deposit<'info> {
 
             // we'll check them to be equal - to ensure all quantity is converted correctly
 
-            let lp_tokens_a = amm
-                .lp_supply
-                .checked_mul(quantity_a)
-                .and_then(|v| v.checked_div(self.reserve_a.amount))
-                .ok_or(AMMError::ArithmeticOverflow)?;
-
-            let lp_tokens_b = amm
-                .lp_supply
-                .checked_mul(quantity_b)
-                .and_then(|v| v.checked_div(self.reserve_b.amount))
-                .ok_or(AMMError::ArithmeticOverflow)?;
+            let lp_tokens_a =
+                proportional_lp_tokens(quantity_a, self.reserve_a.amount, amm.lp_supply)?;
+            let lp_tokens_b =
+                proportional_lp_tokens(quantity_b, self.reserve_b.amount, amm.lp_supply)?;
 
             require!(lp_tokens_a == lp_tokens_b, AMMError::InvalidLiquidity);
 
-            tokens_to_issue = lp_tokens_a;
+            lp_tokens_a
+        };
+
+        // fees are settled on the LP balance held before this deposit, and the
+        // LP tokens minted here earn from now on
+        if let Some(checkpoint) = self.lp_fee_checkpoint.as_deref_mut() {
+            settle_lp_fees(&amm, checkpoint, self.token_lp_account.amount)?;
+            checkpoint.lp_balance = checkpoint
+                .lp_balance
+                .checked_add(tokens_to_issue)
+                .ok_or(AMMError::ArithmeticOverflow)?;
         }
         drop(amm);
 
+        let temp_wsol_account = self.temp_wsol_account.as_ref();
         let transfer_to_reserve_a = CpiContext::new(
             self.token_program.to_account_info(),
             Transfer {
-                from: self.token_a_account.to_account_info(),
+                from: input_account(&self.token_a_account, temp_wsol_account),
                 to: self.reserve_a.to_account_info(),
                 authority: self.signer.to_account_info(),
             },
@@ -139,7 +195,7 @@ impl<'info> Deposit<'info> {
         let transfer_to_reserve_b = CpiContext::new(
             self.token_program.to_account_info(),
             Transfer {
-                from: self.token_b_account.to_account_info(),
+                from: input_account(&self.token_b_account, temp_wsol_account),
                 to: self.reserve_b.to_account_info(),
                 authority: self.signer.to_account_info(),
             },
@@ -172,29 +228,53 @@ impl<'info> Deposit<'info> {
         mint_to(mint_lp_token_ctx, tokens_to_issue)?;
 
         let mut amm = self.amm.load_mut()?;
-        amm.lp_supply += tokens_to_issue;
+        amm.lp_supply = amm
+            .lp_supply
+            .checked_add(tokens_to_issue)
+            .ok_or(AMMError::ArithmeticOverflow)?;
 
         Ok(())
     }
-}
 
-pub fn binary_search_sqrt(value: u128) -> u64 {
-    if value < 2 {
-        return value as u64;
+    pub fn wrap_native(
+        &mut self,
+        quantity_a: u64,
+        quantity_b: u64,
+        bumps: &DepositBumps,
+    ) -> Result<()> {
+        let (native_mint, quantity) =
+            if is_native_a(&self.token_a_mint.key(), &self.token_b_mint.key())? {
+                (&self.token_a_mint, quantity_a)
+            } else {
+                (&self.token_b_mint, quantity_b)
+            };
+        let temp_wsol_account = self
+            .temp_wsol_account
+            .as_ref()
+            .ok_or(AMMError::MissingTempWsolAccount)?;
+
+        wrap_sol(
+            self.system_program.to_account_info(),
+            self.token_program.to_account_info(),
+            self.signer.to_account_info(),
+            (temp_wsol_account.to_account_info(), bumps.temp_wsol_account),
+            native_mint.to_account_info(),
+            quantity,
+        )
     }
 
-    let mut left: u128 = 1;
-    let mut right: u128 = value;
-    let mut ans: u128 = 0;
+    // The deposit takes exactly what was wrapped, this only returns the
+    // temporary account's rent
+    pub fn close_wrapped_native(&mut self) -> Result<()> {
+        let temp_wsol_account = self
+            .temp_wsol_account
+            .as_ref()
+            .ok_or(AMMError::MissingTempWsolAccount)?;
 
-    while left <= right {
-        let mid = left + (right - left) / 2;
-        if mid * mid <= value {
-            ans = mid;
-            left = mid + 1;
-        } else {
-            right = mid - 1;
-        }
+        close_temp_account(
+            self.token_program.to_account_info(),
+            self.signer.to_account_info(),
+            temp_wsol_account.to_account_info(),
+        )
     }
-    ans as u64
 }
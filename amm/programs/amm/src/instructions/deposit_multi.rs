@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{mint_to, Mint, MintTo, Token, TokenAccount},
+};
+
+use crate::{
+    instructions::{
+        multi_pool::{
+            initial_multi_lp_tokens, proportional_deposit_amount, reserve_amount,
+            transfer_to_reserve,
+        },
+        utils::proportional_lp_tokens,
+    },
+    states::{AMMError, MultiPool},
+};
+
+// remaining_accounts: [reserve_0 .. reserve_n-1, user_account_0 .. user_account_n-1]
+
+#[derive(Accounts)]
+pub struct DepositMulti<'info> {
+    #[account(
+        mut,
+        seeds = [b"multi_pool", multi_pool.mints_hash.as_ref()],
+        bump = multi_pool.bump,
+        has_one = lp_mint,
+    )]
+    pub multi_pool: Box<Account<'info, MultiPool>>,
+
+    #[account(mut)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = lp_mint,
+        associated_token::authority = signer
+    )]
+    pub token_lp_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> DepositMulti<'info> {
+    pub fn deposit_multi(
+        &mut self,
+        max_amounts: Vec<u64>,
+        min_lp_out: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let pool = &self.multi_pool;
+        let token_count = pool.token_count as usize;
+        require!(
+            max_amounts.len() == token_count && remaining_accounts.len() == 2 * token_count,
+            AMMError::InvalidPoolAccounts
+        );
+        require!(
+            max_amounts.iter().all(|&amount| amount > 0),
+            AMMError::InvalidQuantity
+        );
+
+        let (reserves, user_accounts) = remaining_accounts.split_at(token_count);
+        let reserve_amounts = reserves
+            .iter()
+            .enumerate()
+            .map(|(i, reserve)| reserve_amount(pool, i, reserve))
+            .collect::<Result<Vec<u64>>>()?;
+
+        let (tokens_to_issue, amounts) = if pool.lp_supply == 0 {
+            // first LP sets the prices, LP[minted] = sum of deposits in whole tokens
+            let tokens_to_issue = initial_multi_lp_tokens(
+                &max_amounts,
+                &pool.decimals[..token_count],
+                pool.lp_decimals,
+            )?;
+
+            (tokens_to_issue, max_amounts)
+        } else {
+            // LP[minted] = min(q_i / v_i X LP[total]), then only the share of
+            // each reserve backing those LP tokens is taken - the rest of
+            // max_amounts stays with the user
+            let mut tokens_to_issue = u64::MAX;
+            for (&amount, &reserve) in max_amounts.iter().zip(&reserve_amounts) {
+                tokens_to_issue =
+                    tokens_to_issue.min(proportional_lp_tokens(amount, reserve, pool.lp_supply)?);
+            }
+
+            let amounts = reserve_amounts
+                .iter()
+                .map(|&reserve| {
+                    proportional_deposit_amount(tokens_to_issue, reserve, pool.lp_supply)
+                })
+                .collect::<Result<Vec<u64>>>()?;
+
+            (tokens_to_issue, amounts)
+        };
+
+        require!(tokens_to_issue > 0, AMMError::InvalidQuantity);
+        require!(tokens_to_issue >= min_lp_out, AMMError::SlippageExceeded);
+
+        for ((reserve, user_account), &amount) in reserves.iter().zip(user_accounts).zip(&amounts) {
+            transfer_to_reserve(
+                self.token_program.to_account_info(),
+                self.signer.to_account_info(),
+                user_account.clone(),
+                reserve.clone(),
+                amount,
+            )?;
+        }
+
+        let mints_hash = self.multi_pool.mints_hash;
+        let seeds: &[&[u8]; 3] = &[b"multi_pool", mints_hash.as_ref(), &[self.multi_pool.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        let mint_lp_token_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.lp_mint.to_account_info(),
+                to: self.token_lp_account.to_account_info(),
+                authority: self.multi_pool.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        mint_to(mint_lp_token_ctx, tokens_to_issue)?;
+
+        self.multi_pool.lp_supply = self
+            .multi_pool
+            .lp_supply
+            .checked_add(tokens_to_issue)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+}
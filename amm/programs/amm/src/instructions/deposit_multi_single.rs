@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{mint_to, Mint, MintTo, Token, TokenAccount},
+};
+
+use crate::{
+    instructions::multi_pool::{multi_single_deposit_lp, reserve_amount, transfer_to_reserve},
+    states::{AMMError, MultiPool},
+};
+
+// remaining_accounts: [reserve, user_account] for the deposited token
+
+#[derive(Accounts)]
+pub struct DepositMultiSingle<'info> {
+    #[account(
+        mut,
+        seeds = [b"multi_pool", multi_pool.mints_hash.as_ref()],
+        bump = multi_pool.bump,
+        has_one = lp_mint,
+    )]
+    pub multi_pool: Box<Account<'info, MultiPool>>,
+
+    #[account(mut)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = lp_mint,
+        associated_token::authority = signer
+    )]
+    pub token_lp_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> DepositMultiSingle<'info> {
+    pub fn deposit_multi_single(
+        &mut self,
+        index: u8,
+        amount_in: u64,
+        min_lp_out: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(amount_in > 0, AMMError::InvalidQuantity);
+        require!(self.multi_pool.lp_supply > 0, AMMError::EmptyPool);
+
+        let [reserve, user_account] = remaining_accounts else {
+            return err!(AMMError::InvalidPoolAccounts);
+        };
+
+        // LP[minted] = LP[total] X ((1 + a / r)^(1/n) - 1)
+        // depositing into one reserve grows prod(reserves) by (1 + a / r), and
+        // the LP supply grows by its n-th root to keep the value per LP token
+        let reserve_before = reserve_amount(&self.multi_pool, index as usize, reserve)?;
+        let tokens_to_issue = multi_single_deposit_lp(
+            amount_in,
+            reserve_before,
+            self.multi_pool.lp_supply,
+            self.multi_pool.token_count,
+            self.multi_pool.fee_bps,
+        )?;
+
+        require!(tokens_to_issue > 0, AMMError::InvalidQuantity);
+        require!(tokens_to_issue >= min_lp_out, AMMError::SlippageExceeded);
+
+        transfer_to_reserve(
+            self.token_program.to_account_info(),
+            self.signer.to_account_info(),
+            user_account.clone(),
+            reserve.clone(),
+            amount_in,
+        )?;
+
+        let mints_hash = self.multi_pool.mints_hash;
+        let seeds: &[&[u8]; 3] = &[b"multi_pool", mints_hash.as_ref(), &[self.multi_pool.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        let mint_lp_token_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.lp_mint.to_account_info(),
+                to: self.token_lp_account.to_account_info(),
+                authority: self.multi_pool.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        mint_to(mint_lp_token_ctx, tokens_to_issue)?;
+
+        self.multi_pool.lp_supply = self
+            .multi_pool
+            .lp_supply
+            .checked_add(tokens_to_issue)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,244 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{
+        mint_to, set_authority, spl_token::instruction::AuthorityType, transfer, Mint, MintTo,
+        SetAuthority, Token, TokenAccount, Transfer,
+    },
+};
+
+use crate::{
+    instructions::{
+        allowlist::check_allowlist,
+        dynamic_fee::pool_price,
+        utils::{initial_lp_tokens, is_proportional_deposit, proportional_lp_tokens},
+    },
+    states::{AMMError, AllowlistEntry, Position, AMM, LAUNCH_ACTIVE},
+};
+
+// Same deposit as `deposit`, but the shares are recorded on a position account
+// and represented by a 1-of-1 NFT instead of minted as LP tokens. The NFT's
+// mint authority is removed once it's minted, and the position can be moved
+// with a plain token transfer.
+
+#[derive(Accounts)]
+pub struct DepositPosition<'info> {
+    #[account(
+        mut,
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", position_mint.key().as_ref()],
+        bump,
+    )]
+    pub position: Box<Account<'info, Position>>,
+
+    #[account(
+        init,
+        payer = signer,
+        mint::decimals = 0,
+        mint::authority = pool_authority,
+    )]
+    pub position_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = signer,
+        associated_token::mint = position_mint,
+        associated_token::authority = signer
+    )]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_a_mint,
+        associated_token::authority = signer
+    )]
+    pub token_a_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_b_mint,
+        associated_token::authority = signer
+    )]
+    pub token_b_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve_a", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = pool_authority
+    )]
+    pub reserve_a: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"reserve_b", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = pool_authority
+    )]
+    pub reserve_b: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: pool authority over token reserves and lp mint
+    #[account(
+        seeds=[b"authority", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    // permissioned pools need one of these
+    #[account(
+        seeds = [b"allowlist", amm.key().as_ref(), signer.key().as_ref()],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Option<Box<Account<'info, AllowlistEntry>>>,
+    pub allowlist_authority: Option<Signer<'info>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> DepositPosition<'info> {
+    pub fn deposit_position(
+        &mut self,
+        quantity_a: u64,
+        quantity_b: u64,
+        bumps: &DepositPositionBumps,
+    ) -> Result<()> {
+        check_allowlist(
+            &*self.amm.load()?,
+            self.allowlist_entry.as_deref(),
+            self.allowlist_authority.as_ref(),
+        )?;
+
+        require!(quantity_a > 0 && quantity_b > 0, AMMError::InvalidQuantity);
+
+        // shares are priced exactly like LP tokens
+        let amm = self.amm.load()?;
+        require!(amm.launch_state != LAUNCH_ACTIVE, AMMError::LaunchActive);
+        let shares = if amm.lp_supply == 0 {
+            initial_lp_tokens(
+                quantity_a,
+                quantity_b,
+                self.token_a_mint.decimals,
+                self.token_b_mint.decimals,
+                amm.lp_decimals,
+            )?
+        } else {
+            require!(
+                is_proportional_deposit(
+                    quantity_a,
+                    quantity_b,
+                    self.reserve_a.amount,
+                    self.reserve_b.amount
+                ),
+                AMMError::InvalidLiquidity
+            );
+
+            let shares_a =
+                proportional_lp_tokens(quantity_a, self.reserve_a.amount, amm.lp_supply)?;
+            let shares_b =
+                proportional_lp_tokens(quantity_b, self.reserve_b.amount, amm.lp_supply)?;
+
+            require!(shares_a == shares_b, AMMError::InvalidLiquidity);
+
+            shares_a
+        };
+        drop(amm);
+
+        let transfer_to_reserve_a = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.token_a_account.to_account_info(),
+                to: self.reserve_a.to_account_info(),
+                authority: self.signer.to_account_info(),
+            },
+        );
+
+        let transfer_to_reserve_b = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.token_b_account.to_account_info(),
+                to: self.reserve_b.to_account_info(),
+                authority: self.signer.to_account_info(),
+            },
+        );
+
+        transfer(transfer_to_reserve_a, quantity_a)?;
+        transfer(transfer_to_reserve_b, quantity_b)?;
+
+        let token_a_mint_key = self.token_a_mint.key();
+        let token_b_mint_key = self.token_b_mint.key();
+
+        let seeds: &[&[u8]; 4] = &[
+            b"authority",
+            token_a_mint_key.as_ref(),
+            token_b_mint_key.as_ref(),
+            &[bumps.pool_authority],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let mint_position_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.position_mint.to_account_info(),
+                to: self.position_token_account.to_account_info(),
+                authority: self.pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        mint_to(mint_position_ctx, 1)?;
+
+        // no one can ever mint a second copy
+        let revoke_mint_authority_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            SetAuthority {
+                current_authority: self.pool_authority.to_account_info(),
+                account_or_mint: self.position_mint.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        set_authority(revoke_mint_authority_ctx, AuthorityType::MintTokens, None)?;
+
+        let mut amm = self.amm.load_mut()?;
+        amm.lp_supply = amm
+            .lp_supply
+            .checked_add(shares)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+
+        self.position.set_inner(Position {
+            amm: self.amm.key(),
+            position_mint: self.position_mint.key(),
+            shares,
+            deposited_a: quantity_a,
+            deposited_b: quantity_b,
+            withdrawn_a: 0,
+            withdrawn_b: 0,
+            entry_price: pool_price(quantity_a, quantity_b),
+            fees_accrued_a: 0,
+            fees_accrued_b: 0,
+            // only fees from later trades count
+            fee_growth_a: amm.fee_growth_a,
+            fee_growth_b: amm.fee_growth_b,
+            created_at: Clock::get()?.unix_timestamp,
+            bump: bumps.position,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{mint_to, transfer, Mint, MintTo, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    instructions::{
+        allowlist::check_allowlist,
+        circuit_breaker::check_circuit_breaker,
+        dynamic_fee::{amount_after_fee, update_swap_fee},
+        lp_fees::{accrue_lp_fee, settle_lp_fees},
+        oracle::check_oracle_price,
+        utils::{proportional_lp_tokens, single_sided_swap_amount, swap_output},
+    },
+    states::{AMMError, AllowlistEntry, LpFeeCheckpoint, PriceFeed, AMM},
+};
+
+#[derive(Accounts)]
+pub struct DepositSingle<'info> {
+    #[account(
+        mut,
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_a_mint,
+        associated_token::authority = signer
+    )]
+    pub token_a_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_b_mint,
+        associated_token::authority = signer
+    )]
+    pub token_b_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = lp_mint,
+        associated_token::authority = signer
+    )]
+    pub token_lp_account: Box<Account<'info, TokenAccount>>,
+
+    // opt-in fee tracking, see create_lp_fee_checkpoint
+    #[account(
+        mut,
+        seeds = [b"lp_fees", amm.key().as_ref(), signer.key().as_ref()],
+        bump = lp_fee_checkpoint.bump,
+    )]
+    pub lp_fee_checkpoint: Option<Box<Account<'info, LpFeeCheckpoint>>>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve_a", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = pool_authority
+    )]
+    pub reserve_a: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"reserve_b", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = pool_authority
+    )]
+    pub reserve_b: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: pool authority over token reserves and lp mint
+    #[account(
+        seeds=[b"authority", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"lp_mint", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    // required when the pool has an oracle configured
+    pub oracle: Option<Account<'info, PriceFeed>>,
+
+    // permissioned pools need one of these
+    #[account(
+        seeds = [b"allowlist", amm.key().as_ref(), signer.key().as_ref()],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Option<Box<Account<'info, AllowlistEntry>>>,
+    pub allowlist_authority: Option<Signer<'info>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> DepositSingle<'info> {
+    pub fn deposit_single(
+        &mut self,
+        amount_in: u64,
+        is_a: bool,
+        min_lp_out: u64,
+        bumps: &DepositSingleBumps,
+    ) -> Result<()> {
+        check_allowlist(
+            &*self.amm.load()?,
+            self.allowlist_entry.as_deref(),
+            self.allowlist_authority.as_ref(),
+        )?;
+
+        require!(amount_in > 0, AMMError::InvalidQuantity);
+
+        let (reserve_in, reserve_out, user_in_account) = if is_a {
+            (&self.reserve_a, &self.reserve_b, &self.token_a_account)
+        } else {
+            (&self.reserve_b, &self.reserve_a, &self.token_b_account)
+        };
+
+        let mut amm = self.amm.load_mut()?;
+        require!(amm.batch_window_slots == 0, AMMError::BatchModeActive);
+        require!(amm.lp_supply > 0, AMMError::EmptyPool);
+
+        let now = Clock::get()?.unix_timestamp;
+        let fee_bps = update_swap_fee(&mut amm, self.reserve_a.amount, self.reserve_b.amount, now);
+
+        // ZAP IN
+        // 1. swap s of the input through the pool
+        // 2. deposit the remaining (a - s) together with the swap output
+        // the swapped tokens never leave the pool, so only the input is transferred
+
+        // Example
+        // Pool - 100 A and 400 B, deposit 44 A
+        // s = Sqrt(100 X 144) - 100 = 20
+        // swap 20 A -> 400 X 20 / 120 = 66 B, pool is now 120 A and 334 B
+        // deposit 24 A and 66 B against it
+
+        // the swapped part pays the swap fee like any other trade
+
+        let swap_amount = single_sided_swap_amount(amount_in, reserve_in.amount, fee_bps)?;
+        let swap_amount_after_fee = amount_after_fee(swap_amount, fee_bps)?;
+        let swap_out = swap_output(swap_amount_after_fee, reserve_in.amount, reserve_out.amount)?;
+
+        let deposit_in = amount_in - swap_amount;
+        let reserve_in_after_swap = reserve_in.amount + swap_amount;
+        let reserve_out_after_swap = reserve_out.amount - swap_out;
+
+        // the deposit keeps the post-swap price, so that is what the oracle sees
+        let (reserve_a_after, reserve_b_after) = if is_a {
+            (reserve_in_after_swap, reserve_out_after_swap)
+        } else {
+            (reserve_out_after_swap, reserve_in_after_swap)
+        };
+        check_oracle_price(
+            &amm,
+            self.oracle.as_ref(),
+            reserve_a_after,
+            reserve_b_after,
+            self.token_a_mint.decimals,
+            self.token_b_mint.decimals,
+            now,
+        )?;
+
+        // the swap leg counts toward the price limit, nothing leaves the pool
+        check_circuit_breaker(
+            &mut amm,
+            self.reserve_a.amount,
+            self.reserve_b.amount,
+            reserve_a_after,
+            reserve_b_after,
+            (0, 0),
+            Clock::get()?.slot,
+        )?;
+
+        // rounding can leave the two sides marginally apart - mint for the smaller one
+        let lp_tokens_in =
+            proportional_lp_tokens(deposit_in, reserve_in_after_swap, amm.lp_supply)?;
+        let lp_tokens_out =
+            proportional_lp_tokens(swap_out, reserve_out_after_swap, amm.lp_supply)?;
+        let tokens_to_issue = lp_tokens_in.min(lp_tokens_out);
+
+        // fees are settled on the LP balance held before this deposit
+        if let Some(checkpoint) = self.lp_fee_checkpoint.as_deref_mut() {
+            settle_lp_fees(&amm, checkpoint, self.token_lp_account.amount)?;
+        }
+
+        // the swap leg's fee is left in the input token's reserve. The
+        // checkpoint moves past it so the depositor's existing balance isn't
+        // credited with a share of their own fee, and the LP tokens minted
+        // here earn from now on
+        accrue_lp_fee(&mut amm, swap_amount - swap_amount_after_fee, is_a);
+        if let Some(checkpoint) = self.lp_fee_checkpoint.as_deref_mut() {
+            checkpoint.fee_growth_a = amm.fee_growth_a;
+            checkpoint.fee_growth_b = amm.fee_growth_b;
+            checkpoint.lp_balance = checkpoint
+                .lp_balance
+                .checked_add(tokens_to_issue)
+                .ok_or(AMMError::ArithmeticOverflow)?;
+        }
+        drop(amm);
+
+        require!(tokens_to_issue > 0, AMMError::InvalidQuantity);
+        require!(tokens_to_issue >= min_lp_out, AMMError::SlippageExceeded);
+
+        let transfer_to_reserve = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: user_in_account.to_account_info(),
+                to: reserve_in.to_account_info(),
+                authority: self.signer.to_account_info(),
+            },
+        );
+
+        transfer(transfer_to_reserve, amount_in)?;
+
+        let token_a_mint_key = self.token_a_mint.key();
+        let token_b_mint_key = self.token_b_mint.key();
+
+        let seeds: &[&[u8]; 4] = &[
+            b"authority",
+            token_a_mint_key.as_ref(),
+            token_b_mint_key.as_ref(),
+            &[bumps.pool_authority],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let mint_lp_token_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.lp_mint.to_account_info(),
+                to: self.token_lp_account.to_account_info(),
+                authority: self.pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        mint_to(mint_lp_token_ctx, tokens_to_issue)?;
+
+        let mut amm = self.amm.load_mut()?;
+        amm.lp_supply = amm
+            .lp_supply
+            .checked_add(tokens_to_issue)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    instructions::utils::to_u64,
+    states::{AMMError, AMM, BPS_DENOMINATOR, VOLATILITY_FEE_DIVISOR, VOLATILITY_HALF_LIFE},
+};
+
+// DYNAMIC FEE
+// Every trade against the reserves first folds the price movement since the
+// previous trade into a volatility accumulator, then charges
+// fee = min(maxFee, minFee + volatility / VOLATILITY_FEE_DIVISOR)
+// The accumulator halves every VOLATILITY_HALF_LIFE seconds, so the fee falls
+// back to minFee once the market calms down. A trade is priced off the
+// movement that came before it - its own price impact raises the next fee.
+
+// Q64.64 price of token A in token B - reserve_b < 2^64 so the shift cannot overflow
+pub fn pool_price(reserve_a: u64, reserve_b: u64) -> u128 {
+    if reserve_a == 0 {
+        return 0;
+    }
+
+    ((reserve_b as u128) << 64) / reserve_a as u128
+}
+
+// |price - lastPrice| / lastPrice in bps, saturating on extreme moves
+pub fn price_move_bps(last_price: u128, price: u128) -> u64 {
+    if last_price == 0 || price == 0 {
+        return 0;
+    }
+
+    last_price
+        .abs_diff(price)
+        .checked_mul(BPS_DENOMINATOR as u128)
+        .map_or(u64::MAX, |scaled| {
+            u64::try_from(scaled / last_price).unwrap_or(u64::MAX)
+        })
+}
+
+pub fn decay_volatility(volatility: u64, elapsed: i64) -> u64 {
+    let half_lives = (elapsed.max(0) / VOLATILITY_HALF_LIFE).min(63);
+    volatility >> half_lives
+}
+
+pub fn dynamic_fee_bps(volatility: u64, min_fee_bps: u16, max_fee_bps: u16) -> u16 {
+    let fee = (min_fee_bps as u64).saturating_add(volatility / VOLATILITY_FEE_DIVISOR);
+    fee.min(max_fee_bps as u64) as u16
+}
+
+// Updates the pool's volatility accumulator for a trade against the given
+// (pre-trade) reserves and returns the fee it pays
+pub fn update_swap_fee(amm: &mut AMM, reserve_a: u64, reserve_b: u64, now: i64) -> u16 {
+    if amm.dynamic_fee == 0 {
+        return amm.min_fee_bps;
+    }
+
+    let price = pool_price(reserve_a, reserve_b);
+    amm.volatility = decay_volatility(amm.volatility, now - amm.last_swap_ts)
+        .saturating_add(price_move_bps(amm.last_price, price));
+    amm.last_price = price;
+    amm.last_swap_ts = now;
+
+    dynamic_fee_bps(amm.volatility, amm.min_fee_bps, amm.max_fee_bps)
+}
+
+// amountIn[after fee] = amountIn X (10000 - fee) / 10000
+// the fee stays in the reserves and accrues to LPs
+pub fn amount_after_fee(amount_in: u64, fee_bps: u16) -> Result<u64> {
+    let amount = (amount_in as u128)
+        .checked_mul((BPS_DENOMINATOR - fee_bps as u64) as u128)
+        .ok_or(AMMError::ArithmeticOverflow)?
+        / BPS_DENOMINATOR as u128;
+
+    to_u64(amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::utils::swap_output;
+
+    // Replays swaps against an in-memory pool the same way the swap
+    // instruction does, returning the fee charged on each one
+    struct SimPool {
+        amm: AMM,
+        reserve_a: u64,
+        reserve_b: u64,
+    }
+
+    impl SimPool {
+        fn new(reserve_a: u64, reserve_b: u64, min_fee_bps: u16, max_fee_bps: u16) -> Self {
+            let mut amm: AMM = bytemuck::Zeroable::zeroed();
+            amm.min_fee_bps = min_fee_bps;
+            amm.max_fee_bps = max_fee_bps;
+            amm.dynamic_fee = 1;
+
+            SimPool {
+                amm,
+                reserve_a,
+                reserve_b,
+            }
+        }
+
+        fn swap(&mut self, now: i64, amount_in: u64, is_a: bool) -> u16 {
+            let fee = update_swap_fee(&mut self.amm, self.reserve_a, self.reserve_b, now);
+            let amount = amount_after_fee(amount_in, fee).unwrap();
+
+            if is_a {
+                let out = swap_output(amount, self.reserve_a, self.reserve_b).unwrap();
+                self.reserve_a += amount_in;
+                self.reserve_b -= out;
+            } else {
+                let out = swap_output(amount, self.reserve_b, self.reserve_a).unwrap();
+                self.reserve_b += amount_in;
+                self.reserve_a -= out;
+            }
+
+            fee
+        }
+
+        fn replay(&mut self, swaps: &[(i64, u64, bool)]) -> Vec<u16> {
+            swaps
+                .iter()
+                .map(|&(now, amount_in, is_a)| self.swap(now, amount_in, is_a))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn fee_stays_at_min_without_price_movement() {
+        let mut pool = SimPool::new(1_000_000_000, 4_000_000_000, 30, 300);
+        let fees = pool.replay(&[(0, 1, true), (10, 1, false), (20, 1, true)]);
+
+        assert_eq!(fees, vec![30, 30, 30]);
+    }
+
+    #[test]
+    fn fee_rises_with_volatility_and_is_capped() {
+        let mut pool = SimPool::new(1_000_000_000, 1_000_000_000, 30, 300);
+
+        // each 10% swap moves the price roughly 20%
+        let fees = pool.replay(&[
+            (0, 100_000_000, true),
+            (1, 100_000_000, false),
+            (2, 100_000_000, true),
+            (3, 100_000_000, false),
+        ]);
+
+        assert_eq!(fees[0], 30);
+        assert!(fees[1] > fees[0]);
+        assert!(fees[2] > fees[1]);
+        assert_eq!(fees[3], 300);
+    }
+
+    #[test]
+    fn fee_decays_back_to_min() {
+        let mut pool = SimPool::new(1_000_000_000, 1_000_000_000, 30, 300);
+        pool.replay(&[(0, 100_000_000, true), (1, 1, true)]);
+        let raised = pool.amm.volatility;
+        assert!(raised > 0);
+
+        // one half-life later the accumulator has halved
+        pool.swap(1 + VOLATILITY_HALF_LIFE, 1, true);
+        assert_eq!(pool.amm.volatility, raised / 2);
+
+        let fee = pool.swap(1 + 64 * VOLATILITY_HALF_LIFE, 1, true);
+        assert_eq!(fee, 30);
+    }
+
+    #[test]
+    fn replays_are_deterministic() {
+        let swaps = [
+            (0, 50_000_000, true),
+            (5, 20_000_000, false),
+            (90, 70_000_000, false),
+            (95, 10_000_000, true),
+            (400, 1_000_000, true),
+        ];
+
+        let mut first = SimPool::new(1_000_000_000, 2_000_000_000, 5, 100);
+        let mut second = SimPool::new(1_000_000_000, 2_000_000_000, 5, 100);
+
+        assert_eq!(first.replay(&swaps), second.replay(&swaps));
+        assert_eq!(first.reserve_a, second.reserve_a);
+        assert_eq!(first.reserve_b, second.reserve_b);
+    }
+
+    #[test]
+    fn fixed_mode_ignores_volatility() {
+        let mut pool = SimPool::new(1_000_000_000, 1_000_000_000, 25, 300);
+        pool.amm.dynamic_fee = 0;
+
+        let fees = pool.replay(&[(0, 300_000_000, true), (1, 300_000_000, false)]);
+        assert_eq!(fees, vec![25, 25]);
+        assert_eq!(pool.amm.volatility, 0);
+    }
+}
@@ -0,0 +1,242 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+use crate::{
+    instructions::{
+        circuit_breaker::check_circuit_breaker,
+        dynamic_fee::{amount_after_fee, update_swap_fee},
+        lp_fees::accrue_lp_fee,
+        oracle::check_oracle_price,
+        utils::swap_output,
+    },
+    states::{AMMError, DcaExecuted, DcaOrder, PriceFeed, AMM, BPS_DENOMINATOR, KEEPER_BOUNTY_BPS},
+};
+
+// Permissionless crank - swaps one tranche of a DCA order through the pool
+// once it is due. The last tranche sells whatever is left, with the minimum
+// output scaled down to match. The next tranche is due `interval` seconds
+// after this one ran, so a late keeper never causes a burst of tranches.
+
+#[derive(Accounts)]
+pub struct ExecuteDca<'info> {
+    #[account(
+        mut,
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve_a", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = pool_authority
+    )]
+    pub reserve_a: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"reserve_b", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = pool_authority
+    )]
+    pub reserve_b: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: pool authority over token reserves and lp mint
+    #[account(
+        seeds=[b"authority", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"dca",
+            amm.key().as_ref(),
+            dca.owner.as_ref(),
+            &dca.dca_id.to_le_bytes()
+        ],
+        bump = dca.bump,
+        has_one = input_vault,
+        has_one = output_vault,
+    )]
+    pub dca: Box<Account<'info, DcaOrder>>,
+
+    #[account(mut)]
+    pub input_vault: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub output_vault: Box<Account<'info, TokenAccount>>,
+
+    // the tip is paid in the output token
+    #[account(
+        mut,
+        token::mint = output_vault.mint,
+        token::authority = keeper
+    )]
+    pub keeper_token_account: Box<Account<'info, TokenAccount>>,
+
+    // required when the pool has an oracle configured
+    pub oracle: Option<Account<'info, PriceFeed>>,
+
+    pub token_program: Program<'info, Token>,
+    pub keeper: Signer<'info>,
+}
+
+impl<'info> ExecuteDca<'info> {
+    pub fn execute_dca(&mut self, bumps: &ExecuteDcaBumps) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= self.dca.next_execution_ts, AMMError::DcaNotDue);
+
+        let amount_in = self.dca.amount_per_interval.min(self.input_vault.amount);
+        require!(amount_in > 0, AMMError::InvalidQuantity);
+
+        // minOut = minOutPerInterval X amountIn / amountPerInterval
+        let min_amount_out = (self.dca.min_out_per_interval as u128 * amount_in as u128
+            / self.dca.amount_per_interval as u128) as u64;
+
+        let (reserve_in, reserve_out) = if self.dca.sell_a {
+            (&self.reserve_a, &self.reserve_b)
+        } else {
+            (&self.reserve_b, &self.reserve_a)
+        };
+
+        // priced exactly like `swap`
+        let mut amm = self.amm.load_mut()?;
+        require!(amm.batch_window_slots == 0, AMMError::BatchModeActive);
+        let fee_bps = update_swap_fee(&mut amm, self.reserve_a.amount, self.reserve_b.amount, now);
+        let amount_in_after_fee = amount_after_fee(amount_in, fee_bps)?;
+        let amount_out = swap_output(amount_in_after_fee, reserve_in.amount, reserve_out.amount)?;
+        require!(
+            amount_out <= reserve_out.amount,
+            AMMError::InsufficientReserves
+        );
+
+        // tip = amountOut X KEEPER_BOUNTY_BPS / 10000
+        let keeper_tip =
+            (amount_out as u128 * KEEPER_BOUNTY_BPS as u128 / BPS_DENOMINATOR as u128) as u64;
+        require!(
+            amount_out - keeper_tip >= min_amount_out,
+            AMMError::SlippageExceeded
+        );
+
+        let reserve_in_after = reserve_in
+            .amount
+            .checked_add(amount_in)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+        let reserve_out_after = reserve_out.amount - amount_out;
+        let (reserve_a_after, reserve_b_after, outflow) = if self.dca.sell_a {
+            (reserve_in_after, reserve_out_after, (0, amount_out))
+        } else {
+            (reserve_out_after, reserve_in_after, (amount_out, 0))
+        };
+
+        check_oracle_price(
+            &amm,
+            self.oracle.as_ref(),
+            reserve_a_after,
+            reserve_b_after,
+            self.token_a_mint.decimals,
+            self.token_b_mint.decimals,
+            now,
+        )?;
+        check_circuit_breaker(
+            &mut amm,
+            self.reserve_a.amount,
+            self.reserve_b.amount,
+            reserve_a_after,
+            reserve_b_after,
+            outflow,
+            Clock::get()?.slot,
+        )?;
+        accrue_lp_fee(&mut amm, amount_in - amount_in_after_fee, self.dca.sell_a);
+        drop(amm);
+
+        let amm_key = self.amm.key();
+        let dca_id = self.dca.dca_id.to_le_bytes();
+        let seeds: &[&[u8]; 5] = &[
+            b"dca",
+            amm_key.as_ref(),
+            self.dca.owner.as_ref(),
+            &dca_id,
+            &[self.dca.bump],
+        ];
+        let dca_signer_seeds = &[&seeds[..]];
+
+        let transfer_to_reserve_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.input_vault.to_account_info(),
+                to: reserve_in.to_account_info(),
+                authority: self.dca.to_account_info(),
+            },
+            dca_signer_seeds,
+        );
+
+        transfer(transfer_to_reserve_ctx, amount_in)?;
+
+        let token_a_mint_key = self.token_a_mint.key();
+        let token_b_mint_key = self.token_b_mint.key();
+
+        let seeds: &[&[u8]; 4] = &[
+            b"authority",
+            token_a_mint_key.as_ref(),
+            token_b_mint_key.as_ref(),
+            &[bumps.pool_authority],
+        ];
+        let pool_signer_seeds = &[&seeds[..]];
+
+        let transfer_to_vault_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: reserve_out.to_account_info(),
+                to: self.output_vault.to_account_info(),
+                authority: self.pool_authority.to_account_info(),
+            },
+            pool_signer_seeds,
+        );
+
+        transfer(transfer_to_vault_ctx, amount_out - keeper_tip)?;
+
+        if keeper_tip > 0 {
+            let pay_keeper_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: reserve_out.to_account_info(),
+                    to: self.keeper_token_account.to_account_info(),
+                    authority: self.pool_authority.to_account_info(),
+                },
+                pool_signer_seeds,
+            );
+
+            transfer(pay_keeper_ctx, keeper_tip)?;
+        }
+
+        let dca = &mut self.dca;
+        dca.next_execution_ts = now
+            .checked_add(dca.interval)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+        dca.total_in = dca
+            .total_in
+            .checked_add(amount_in)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+        dca.total_out = dca
+            .total_out
+            .checked_add(amount_out - keeper_tip)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+
+        emit!(DcaExecuted {
+            amm: amm_key,
+            dca: dca.key(),
+            amount_in,
+            amount_out: amount_out - keeper_tip,
+            keeper_tip,
+        });
+
+        Ok(())
+    }
+}
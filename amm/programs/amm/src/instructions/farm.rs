@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    instructions::utils::to_u64,
+    states::{AMMError, Farm, FarmPosition, REWARD_PRECISION},
+};
+
+// Farm rewards accrue through a per-LP-token accumulator per reward mint:
+// rewardPerShare += elapsed * emissionsPerSecond * PRECISION / totalStaked
+// A position is owed amount * rewardPerShare / PRECISION - rewardDebt, where
+// rewardDebt is the same product taken when the position last changed.
+
+pub fn update_farm_rewards(farm: &mut Farm, now: i64) -> Result<()> {
+    let total_staked = farm.total_staked;
+
+    for reward in farm.rewards.iter_mut().take(farm.reward_count as usize) {
+        if now <= reward.last_update_ts {
+            continue;
+        }
+
+        if total_staked == 0 {
+            // nothing is emitted while nobody is staked - push the end out so
+            // the funded amount is still paid out in full
+            if reward.end_ts > reward.last_update_ts {
+                reward.end_ts = reward
+                    .end_ts
+                    .checked_add(now - reward.last_update_ts)
+                    .ok_or(AMMError::ArithmeticOverflow)?;
+            }
+            reward.last_update_ts = now;
+            continue;
+        }
+
+        let accrue_until = now.min(reward.end_ts);
+        if accrue_until > reward.last_update_ts {
+            let emitted = ((accrue_until - reward.last_update_ts) as u128)
+                .checked_mul(reward.emissions_per_second as u128)
+                .ok_or(AMMError::ArithmeticOverflow)?;
+
+            let reward_per_share = emitted
+                .checked_mul(REWARD_PRECISION)
+                .ok_or(AMMError::ArithmeticOverflow)?
+                .checked_div(total_staked as u128)
+                .ok_or(AMMError::ArithmeticOverflow)?;
+
+            reward.reward_per_share = reward
+                .reward_per_share
+                .checked_add(reward_per_share)
+                .ok_or(AMMError::ArithmeticOverflow)?;
+        }
+        reward.last_update_ts = now;
+    }
+
+    Ok(())
+}
+
+fn accrued_rewards(amount: u64, reward_per_share: u128) -> Result<u128> {
+    Ok((amount as u128)
+        .checked_mul(reward_per_share)
+        .ok_or(AMMError::ArithmeticOverflow)?
+        / REWARD_PRECISION)
+}
+
+// Moves everything the position has earned into `rewards_owed`. Must run after
+// `update_farm_rewards` and before the staked amount changes.
+pub fn settle_farm_position(farm: &Farm, position: &mut FarmPosition) -> Result<()> {
+    for i in 0..farm.reward_count as usize {
+        let accrued = accrued_rewards(position.amount, farm.rewards[i].reward_per_share)?;
+        let pending = to_u64(accrued.saturating_sub(position.reward_debt[i]))?;
+
+        position.rewards_owed[i] = position.rewards_owed[i]
+            .checked_add(pending)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+    }
+
+    Ok(())
+}
+
+// Resets the debts against the position's new staked amount
+pub fn checkpoint_farm_position(farm: &Farm, position: &mut FarmPosition) -> Result<()> {
+    for i in 0..farm.reward_count as usize {
+        position.reward_debt[i] =
+            accrued_rewards(position.amount, farm.rewards[i].reward_per_share)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,353 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::{
+    close_account, transfer, CloseAccount, Mint, Token, TokenAccount, Transfer,
+};
+
+use crate::{
+    instructions::{
+        circuit_breaker::check_circuit_breaker,
+        dynamic_fee::{amount_after_fee, update_swap_fee},
+        lp_fees::accrue_lp_fee,
+        oracle::check_oracle_price,
+        utils::swap_output,
+    },
+    states::{
+        AMMError, LimitOrder, LimitOrderFilled, PriceFeed, AMM, BPS_DENOMINATOR, KEEPER_BOUNTY_BPS,
+    },
+};
+
+// remaining_accounts per order:
+// [order, order_vault, owner_token_in_account, owner_token_out_account, owner]
+
+// Permissionless crank, run by keepers after swaps move the price. Orders are
+// filled in the order given, each one as a swap against the pool at the
+// reserves left by the previous fill - so opposite orders in the same crank
+// trade against each other through the curve. Orders the pool can't pay
+// min_amount_out for (after the keeper bounty), or whose fill the oracle band
+// or circuit breaker rejects, are skipped and stay open - a skipped order
+// leaves the pool state as it found it.
+// Filled orders are closed and their rent goes back to the owner, along with
+// anything sent to the vault on top of the escrow.
+
+// Same as Anchor's `close` constraint, for an order passed in remaining_accounts
+fn close_order(order: &AccountInfo, destination: &AccountInfo) -> Result<()> {
+    let lamports = destination
+        .lamports()
+        .checked_add(order.lamports())
+        .ok_or(AMMError::ArithmeticOverflow)?;
+    **destination.try_borrow_mut_lamports()? = lamports;
+    **order.try_borrow_mut_lamports()? = 0;
+
+    order.assign(&system_program::ID);
+    order.realloc(0, false).map_err(Into::into)
+}
+
+#[derive(Accounts)]
+pub struct FillOrders<'info> {
+    #[account(
+        mut,
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve_a", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = pool_authority
+    )]
+    pub reserve_a: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"reserve_b", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = pool_authority
+    )]
+    pub reserve_b: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: pool authority over token reserves and lp mint
+    #[account(
+        seeds=[b"authority", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    // the bounty is paid in each order's output token
+    #[account(
+        mut,
+        token::mint = token_a_mint,
+        token::authority = keeper
+    )]
+    pub keeper_token_a_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = token_b_mint,
+        token::authority = keeper
+    )]
+    pub keeper_token_b_account: Box<Account<'info, TokenAccount>>,
+
+    // required when the pool has an oracle configured
+    pub oracle: Option<Account<'info, PriceFeed>>,
+
+    pub token_program: Program<'info, Token>,
+    pub keeper: Signer<'info>,
+}
+
+impl<'info> FillOrders<'info> {
+    pub fn fill_orders(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+        bumps: &FillOrdersBumps,
+    ) -> Result<()> {
+        let orders = remaining_accounts.chunks_exact(5);
+        require!(
+            !remaining_accounts.is_empty() && orders.remainder().is_empty(),
+            AMMError::InvalidOrder
+        );
+
+        let amm_key = self.amm.key();
+        let token_a_mint_key = self.token_a_mint.key();
+        let token_b_mint_key = self.token_b_mint.key();
+
+        let seeds: &[&[u8]; 4] = &[
+            b"authority",
+            token_a_mint_key.as_ref(),
+            token_b_mint_key.as_ref(),
+            &[bumps.pool_authority],
+        ];
+        let pool_signer_seeds = &[&seeds[..]];
+
+        let now = Clock::get()?.unix_timestamp;
+        let slot = Clock::get()?.slot;
+        let mut reserve_a = self.reserve_a.amount;
+        let mut reserve_b = self.reserve_b.amount;
+        let mut filled = 0;
+
+        let mut amm = self.amm.load_mut()?;
+        require!(amm.batch_window_slots == 0, AMMError::BatchModeActive);
+        // a missing oracle is the keeper's mistake and fails the crank, unlike
+        // a price guard tripping on a single order
+        require!(
+            amm.oracle == Pubkey::default()
+                || self
+                    .oracle
+                    .as_ref()
+                    .is_some_and(|oracle| oracle.key() == amm.oracle),
+            AMMError::InvalidOracle
+        );
+
+        for accounts in orders {
+            let [order_info, vault_info, owner_in_info, owner_out_info, owner_info] = accounts
+            else {
+                return err!(AMMError::InvalidOrder);
+            };
+
+            require_keys_eq!(*order_info.owner, crate::ID, AMMError::InvalidOrder);
+            let order = LimitOrder::try_deserialize(&mut &order_info.try_borrow_data()?[..])?;
+            require_keys_eq!(order.amm, amm_key, AMMError::InvalidOrder);
+            require_keys_eq!(vault_info.key(), order.vault, AMMError::InvalidOrder);
+            require_keys_eq!(owner_info.key(), order.owner, AMMError::InvalidOrder);
+
+            let (mint_in, mint_out, reserve_in, reserve_out, keeper_out_account) = if order.sell_a {
+                (
+                    token_a_mint_key,
+                    token_b_mint_key,
+                    &self.reserve_a,
+                    &self.reserve_b,
+                    &self.keeper_token_b_account,
+                )
+            } else {
+                (
+                    token_b_mint_key,
+                    token_a_mint_key,
+                    &self.reserve_b,
+                    &self.reserve_a,
+                    &self.keeper_token_a_account,
+                )
+            };
+            let owner_in_account =
+                TokenAccount::try_deserialize(&mut &owner_in_info.try_borrow_data()?[..])?;
+            require!(
+                owner_in_account.mint == mint_in && owner_in_account.owner == order.owner,
+                AMMError::InvalidOrder
+            );
+            let owner_out_account =
+                TokenAccount::try_deserialize(&mut &owner_out_info.try_borrow_data()?[..])?;
+            require!(
+                owner_out_account.mint == mint_out && owner_out_account.owner == order.owner,
+                AMMError::InvalidOrder
+            );
+
+            // priced exactly like `swap`
+            let (reserve_in_amount, reserve_out_amount) = if order.sell_a {
+                (reserve_a, reserve_b)
+            } else {
+                (reserve_b, reserve_a)
+            };
+
+            let pool_before = *amm;
+            let fee_bps = update_swap_fee(&mut amm, reserve_a, reserve_b, now);
+            let amount_in_after_fee = amount_after_fee(order.amount_in, fee_bps)?;
+            let amount_out =
+                swap_output(amount_in_after_fee, reserve_in_amount, reserve_out_amount)?;
+
+            // bounty = amountOut X KEEPER_BOUNTY_BPS / 10000
+            let keeper_bounty =
+                (amount_out as u128 * KEEPER_BOUNTY_BPS as u128 / BPS_DENOMINATOR as u128) as u64;
+            if amount_out - keeper_bounty < order.min_amount_out {
+                *amm = pool_before;
+                continue;
+            }
+
+            let reserve_in_after = reserve_in_amount
+                .checked_add(order.amount_in)
+                .ok_or(AMMError::ArithmeticOverflow)?;
+            let reserve_out_after = reserve_out_amount - amount_out;
+            let (reserve_a_after, reserve_b_after, outflow) = if order.sell_a {
+                (reserve_in_after, reserve_out_after, (0, amount_out))
+            } else {
+                (reserve_out_after, reserve_in_after, (amount_out, 0))
+            };
+
+            let guarded = check_oracle_price(
+                &amm,
+                self.oracle.as_ref(),
+                reserve_a_after,
+                reserve_b_after,
+                self.token_a_mint.decimals,
+                self.token_b_mint.decimals,
+                now,
+            )
+            .and_then(|()| {
+                check_circuit_breaker(
+                    &mut amm,
+                    reserve_a,
+                    reserve_b,
+                    reserve_a_after,
+                    reserve_b_after,
+                    outflow,
+                    slot,
+                )
+            });
+            if guarded.is_err() {
+                *amm = pool_before;
+                continue;
+            }
+
+            accrue_lp_fee(
+                &mut amm,
+                order.amount_in - amount_in_after_fee,
+                order.sell_a,
+            );
+
+            let order_id = order.order_id.to_le_bytes();
+            let seeds: &[&[u8]; 5] = &[
+                b"limit_order",
+                amm_key.as_ref(),
+                order.owner.as_ref(),
+                &order_id,
+                &[order.bump],
+            ];
+            let order_signer_seeds = &[&seeds[..]];
+
+            let transfer_to_reserve_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: vault_info.clone(),
+                    to: reserve_in.to_account_info(),
+                    authority: order_info.clone(),
+                },
+                order_signer_seeds,
+            );
+
+            transfer(transfer_to_reserve_ctx, order.amount_in)?;
+
+            // tokens sent to the vault on top of the escrow would keep it
+            // from closing, they go back to the owner
+            let leftover =
+                TokenAccount::try_deserialize(&mut &vault_info.try_borrow_data()?[..])?.amount;
+            if leftover > 0 {
+                let refund_leftover_ctx = CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    Transfer {
+                        from: vault_info.clone(),
+                        to: owner_in_info.clone(),
+                        authority: order_info.clone(),
+                    },
+                    order_signer_seeds,
+                );
+
+                transfer(refund_leftover_ctx, leftover)?;
+            }
+
+            let transfer_to_owner_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: reserve_out.to_account_info(),
+                    to: owner_out_info.clone(),
+                    authority: self.pool_authority.to_account_info(),
+                },
+                pool_signer_seeds,
+            );
+
+            transfer(transfer_to_owner_ctx, amount_out - keeper_bounty)?;
+
+            if keeper_bounty > 0 {
+                let pay_keeper_ctx = CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    Transfer {
+                        from: reserve_out.to_account_info(),
+                        to: keeper_out_account.to_account_info(),
+                        authority: self.pool_authority.to_account_info(),
+                    },
+                    pool_signer_seeds,
+                );
+
+                transfer(pay_keeper_ctx, keeper_bounty)?;
+            }
+
+            let close_vault_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                CloseAccount {
+                    account: vault_info.clone(),
+                    destination: owner_info.clone(),
+                    authority: order_info.clone(),
+                },
+                order_signer_seeds,
+            );
+
+            close_account(close_vault_ctx)?;
+
+            // the fee stays in the reserve, the output (bounty included) leaves it
+            if order.sell_a {
+                reserve_a += order.amount_in;
+                reserve_b -= amount_out;
+            } else {
+                reserve_b += order.amount_in;
+                reserve_a -= amount_out;
+            }
+            filled += 1;
+
+            emit!(LimitOrderFilled {
+                amm: amm_key,
+                order: order_info.key(),
+                owner: order.owner,
+                amount_in: order.amount_in,
+                amount_out: amount_out - keeper_bounty,
+                keeper_bounty,
+            });
+
+            close_order(order_info, owner_info)?;
+        }
+
+        require!(filled > 0, AMMError::NoEligibleOrders);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Token, TokenAccount, Transfer};
+
+use crate::{
+    instructions::{farm::update_farm_rewards, utils::to_u64},
+    states::{AMMError, Farm},
+};
+
+#[derive(Accounts)]
+#[instruction(reward_index: u8)]
+pub struct FundFarmReward<'info> {
+    #[account(
+        mut,
+        seeds = [b"farm", farm.amm.as_ref()],
+        bump = farm.bump,
+        has_one = authority @ AMMError::Unauthorized,
+        constraint = reward_index < farm.reward_count @ AMMError::InvalidReward,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(
+        mut,
+        address = farm.rewards[reward_index as usize].vault @ AMMError::InvalidReward
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::authority = authority)]
+    pub funder_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub authority: Signer<'info>,
+}
+
+impl<'info> FundFarmReward<'info> {
+    pub fn fund_farm_reward(
+        &mut self,
+        reward_index: u8,
+        amount: u64,
+        emissions_per_second: u64,
+    ) -> Result<()> {
+        require!(emissions_per_second > 0, AMMError::InvalidQuantity);
+
+        let now = Clock::get()?.unix_timestamp;
+        update_farm_rewards(&mut self.farm, now)?;
+
+        let reward = &mut self.farm.rewards[reward_index as usize];
+
+        // whatever has not been emitted yet is rolled into the new schedule
+        // end = now + (unemitted + amount) / emissionsPerSecond
+        let remaining_secs = reward.end_ts.saturating_sub(now).max(0) as u128;
+        let unemitted = remaining_secs
+            .checked_mul(reward.emissions_per_second as u128)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+        let total = unemitted
+            .checked_add(amount as u128)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+
+        let duration = to_u64(total / emissions_per_second as u128)?;
+        reward.end_ts = now
+            .checked_add(i64::try_from(duration).map_err(|_| AMMError::ArithmeticOverflow)?)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+        reward.emissions_per_second = emissions_per_second;
+
+        if amount > 0 {
+            let fund_reward_ctx = CpiContext::new(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.funder_token_account.to_account_info(),
+                    to: self.reward_vault.to_account_info(),
+                    authority: self.authority.to_account_info(),
+                },
+            );
+
+            transfer(fund_reward_ctx, amount)?;
+        }
+
+        Ok(())
+    }
+}
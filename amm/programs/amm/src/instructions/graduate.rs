@@ -0,0 +1,258 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{
+    burn, close_account, mint_to, transfer, Burn, CloseAccount, Mint, MintTo, Token, TokenAccount,
+    Transfer,
+};
+
+use crate::{
+    instructions::{launch::graduated_base_reserve, utils::initial_lp_tokens},
+    states::{AMMError, LaunchGraduated, AMM, LAUNCH_ACTIVE, LAUNCH_GRADUATED},
+};
+
+// Anyone can graduate a launch pool once the quote tokens it actually holds
+// reach the threshold. The virtual reserves are dropped and the real reserves
+// move into the pair's standard pool, which trades as a normal
+// constant-product pool from then on. Base tokens beyond what the real quote
+// reserve supports at the launch price are burned so graduation doesn't move
+// the price, and the LP minted for the reserves goes to a vault owned by the
+// pool authority that no instruction withdraws from - the launch liquidity is
+// locked for good. The launch accounts are closed and their rent goes back to
+// the creator.
+
+#[derive(Accounts)]
+pub struct Graduate<'info> {
+    #[account(
+        mut,
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        mut,
+        seeds=[b"launch", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        has_one = creator @ AMMError::Unauthorized,
+        close = creator,
+    )]
+    pub launch: AccountLoader<'info, AMM>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve_a", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = pool_authority
+    )]
+    pub reserve_a: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"reserve_b", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = pool_authority
+    )]
+    pub reserve_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"launch_reserve_a", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = pool_authority
+    )]
+    pub launch_reserve_a: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"launch_reserve_b", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = pool_authority
+    )]
+    pub launch_reserve_b: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: pool authority over token reserves and lp mint
+    #[account(
+        seeds=[b"authority", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    // mutable since the excess base tokens are burned
+    #[account(mut)]
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    #[account(mut)]
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"lp_mint", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"graduated_lp_vault", amm.key().as_ref()],
+        bump,
+        token::mint = lp_mint,
+        token::authority = pool_authority
+    )]
+    pub graduated_lp_vault: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: receives the launch accounts' rent, checked against the launch pool
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> Graduate<'info> {
+    pub fn graduate(&mut self, bumps: &GraduateBumps) -> Result<()> {
+        let launch = self.launch.load()?;
+        require!(
+            launch.launch_state == LAUNCH_ACTIVE,
+            AMMError::InvalidLaunch
+        );
+
+        // the virtual reserves sit on the quote side
+        let base_is_a = launch.virtual_reserve_b > 0;
+        let (base_mint, launch_base_reserve, launch_quote_reserve, virtual_quote) = if base_is_a {
+            (
+                &self.token_a_mint,
+                &self.launch_reserve_a,
+                &self.launch_reserve_b,
+                launch.virtual_reserve_b,
+            )
+        } else {
+            (
+                &self.token_b_mint,
+                &self.launch_reserve_b,
+                &self.launch_reserve_a,
+                launch.virtual_reserve_a,
+            )
+        };
+        let (reserve_base, reserve_quote) = if base_is_a {
+            (&self.reserve_a, &self.reserve_b)
+        } else {
+            (&self.reserve_b, &self.reserve_a)
+        };
+
+        let quote_reserve = launch_quote_reserve.amount;
+        require!(
+            quote_reserve >= launch.graduation_quote_reserve,
+            AMMError::GraduationThresholdNotReached
+        );
+        drop(launch);
+
+        let base_kept =
+            graduated_base_reserve(launch_base_reserve.amount, quote_reserve, virtual_quote)?;
+        require!(
+            base_kept > 0 && quote_reserve > 0,
+            AMMError::InsufficientReserves
+        );
+        let base_burned = launch_base_reserve.amount - base_kept;
+
+        // deposits are refused while the launch is active, so the standard
+        // pool is still empty
+        let (amount_a, amount_b) = if base_is_a {
+            (base_kept, quote_reserve)
+        } else {
+            (quote_reserve, base_kept)
+        };
+        let amm = self.amm.load()?;
+        require!(amm.launch_state == LAUNCH_ACTIVE, AMMError::InvalidLaunch);
+        let lp_tokens = initial_lp_tokens(
+            amount_a,
+            amount_b,
+            self.token_a_mint.decimals,
+            self.token_b_mint.decimals,
+            amm.lp_decimals,
+        )?;
+        drop(amm);
+
+        let token_a_mint_key = self.token_a_mint.key();
+        let token_b_mint_key = self.token_b_mint.key();
+
+        let seeds: &[&[u8]; 4] = &[
+            b"authority",
+            token_a_mint_key.as_ref(),
+            token_b_mint_key.as_ref(),
+            &[bumps.pool_authority],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        if base_burned > 0 {
+            let burn_base_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Burn {
+                    mint: base_mint.to_account_info(),
+                    from: launch_base_reserve.to_account_info(),
+                    authority: self.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            burn(burn_base_ctx, base_burned)?;
+        }
+
+        for (from, to, amount) in [
+            (launch_base_reserve, reserve_base, base_kept),
+            (launch_quote_reserve, reserve_quote, quote_reserve),
+        ] {
+            let transfer_to_pool_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: from.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: self.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            );
+            transfer(transfer_to_pool_ctx, amount)?;
+
+            let close_launch_reserve_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                CloseAccount {
+                    account: from.to_account_info(),
+                    destination: self.creator.to_account_info(),
+                    authority: self.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            );
+            close_account(close_launch_reserve_ctx)?;
+        }
+
+        let mint_lp_token_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.lp_mint.to_account_info(),
+                to: self.graduated_lp_vault.to_account_info(),
+                authority: self.pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        mint_to(mint_lp_token_ctx, lp_tokens)?;
+
+        let mut amm = self.amm.load_mut()?;
+        amm.lp_supply = amm
+            .lp_supply
+            .checked_add(lp_tokens)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+        amm.launch_state = LAUNCH_GRADUATED;
+
+        emit!(LaunchGraduated {
+            amm: self.amm.key(),
+            quote_reserve,
+            base_burned,
+            lp_locked: lp_tokens,
+        });
+
+        Ok(())
+    }
+}
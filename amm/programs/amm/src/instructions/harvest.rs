@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{transfer, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    instructions::farm::{checkpoint_farm_position, settle_farm_position, update_farm_rewards},
+    states::{AMMError, Farm, FarmPosition},
+};
+
+// Pays out one reward mint - farms with several rewards harvest once per mint
+
+#[derive(Accounts)]
+#[instruction(reward_index: u8)]
+pub struct Harvest<'info> {
+    #[account(
+        mut,
+        seeds = [b"farm", farm.amm.as_ref()],
+        bump = farm.bump,
+        constraint = reward_index < farm.reward_count @ AMMError::InvalidReward,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(
+        mut,
+        seeds = [b"farm_position", farm.key().as_ref(), signer.key().as_ref()],
+        bump = position.bump,
+    )]
+    pub position: Box<Account<'info, FarmPosition>>,
+
+    #[account(
+        mut,
+        address = farm.rewards[reward_index as usize].vault @ AMMError::InvalidReward
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = reward_mint,
+        associated_token::authority = signer
+    )]
+    pub reward_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = farm.rewards[reward_index as usize].mint @ AMMError::InvalidReward)]
+    pub reward_mint: Box<Account<'info, Mint>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> Harvest<'info> {
+    pub fn harvest(&mut self, reward_index: u8) -> Result<()> {
+        update_farm_rewards(&mut self.farm, Clock::get()?.unix_timestamp)?;
+        settle_farm_position(&self.farm, &mut self.position)?;
+        checkpoint_farm_position(&self.farm, &mut self.position)?;
+
+        let index = reward_index as usize;
+        let owed = self.position.rewards_owed[index];
+        if owed == 0 {
+            return Ok(());
+        }
+        self.position.rewards_owed[index] = 0;
+
+        let amm_key = self.farm.amm;
+        let seeds: &[&[u8]; 3] = &[b"farm", amm_key.as_ref(), &[self.farm.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        let harvest_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.reward_vault.to_account_info(),
+                to: self.reward_token_account.to_account_info(),
+                authority: self.farm.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(harvest_ctx, owed)
+    }
+}
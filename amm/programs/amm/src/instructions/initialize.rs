@@ -1,7 +1,18 @@
-use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_lang::{prelude::*, solana_program::program_pack::Pack};
+use anchor_spl::{
+    metadata::{
+        create_metadata_accounts_v3,
+        mpl_token_metadata::{types::DataV2, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH},
+        update_metadata_accounts_v2, CreateMetadataAccountsV3, Metadata, MetadataAccount,
+        UpdateMetadataAccountsV2,
+    },
+    token::{initialize_mint2, spl_token, InitializeMint2, Mint, Token, TokenAccount},
+};
 
-use crate::states::{AMMError, AMM};
+use crate::{
+    instructions::utils::create_pda_account,
+    states::{AMMError, AMM, AMM_VERSION},
+};
 
 #[derive(Accounts)]
 pub struct Initialize<'info> {
@@ -45,19 +56,47 @@ pub struct Initialize<'info> {
     pub token_a_mint: Box<Account<'info, Mint>>,
     pub token_b_mint: Box<Account<'info, Mint>>,
 
+    // SPL mints cannot be closed, so a pair re-created after `close_pool`
+    // picks up the lp_mint left behind by the previous pool. Pools created
+    // before the LP decimals followed the pair left 6-decimal mints behind,
+    // so the mint is created by the handler and an existing one is reused
+    // with the decimals it has.
+    /// CHECK: created or loaded by the handler
     #[account(
-        init,
-        payer = signer,
+        mut,
         seeds = [b"lp_mint", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
         bump,
-        mint::decimals = 6,
-        mint::authority = pool_authority,
-        mint::freeze_authority = pool_authority
     )]
-    pub lp_mint: Box<Account<'info, Mint>>,
+    pub lp_mint: UncheckedAccount<'info>,
+
+    /// CHECK: created by the token metadata program, PDA validated by seeds
+    #[account(
+        mut,
+        seeds = [b"metadata", metadata_program.key().as_ref(), lp_mint.key().as_ref()],
+        bump,
+        seeds::program = metadata_program.key(),
+    )]
+    pub lp_metadata: UncheckedAccount<'info>,
+
+    // the pair's token metadata names the LP token, mints without metadata
+    // are named after their address
+    #[account(
+        seeds = [b"metadata", metadata_program.key().as_ref(), token_a_mint.key().as_ref()],
+        bump,
+        seeds::program = metadata_program.key(),
+    )]
+    pub token_a_metadata: Option<Box<Account<'info, MetadataAccount>>>,
+    #[account(
+        seeds = [b"metadata", metadata_program.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        seeds::program = metadata_program.key(),
+    )]
+    pub token_b_metadata: Option<Box<Account<'info, MetadataAccount>>>,
 
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
+    pub metadata_program: Program<'info, Metadata>,
+    pub rent: Sysvar<'info, Rent>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -65,16 +104,184 @@ pub struct Initialize<'info> {
 
 impl<'info> Initialize<'info> {
     pub fn initialize_amm_pool(&mut self, bumps: &InitializeBumps) -> Result<()> {
+        let clock = Clock::get()?;
+        let lp_decimals = self.init_lp_mint(bumps)?;
+
         let mut amm = self.amm.load_init()?;
+        amm.version = AMM_VERSION;
         amm.token_a_mint = self.token_a_mint.key();
         amm.token_b_mint = self.token_b_mint.key();
         amm.lp_mint = self.lp_mint.key();
         amm.lp_bump = bumps.lp_mint;
+        amm.lp_decimals = lp_decimals;
         amm.reserve_a = self.reserve_a.key();
         amm.reserve_b = self.reserve_b.key();
         amm.pool_authority = self.pool_authority.key();
         amm.lp_supply = 0;
+        amm.creator = self.signer.key();
+        amm.created_slot = clock.slot;
+        amm.created_at = clock.unix_timestamp;
 
         Ok(())
     }
+
+    // Creates the lp_mint, or loads the one left behind by a closed pool for
+    // the same pair, and returns its decimals
+    fn init_lp_mint(&self, bumps: &InitializeBumps) -> Result<u8> {
+        let lp_mint_info = self.lp_mint.to_account_info();
+        if !lp_mint_info.data_is_empty() {
+            let lp_mint = Mint::try_deserialize(&mut &lp_mint_info.data.borrow()[..])?;
+            return Ok(lp_mint.decimals);
+        }
+
+        let token_a_mint_key = self.token_a_mint.key();
+        let token_b_mint_key = self.token_b_mint.key();
+
+        let seeds: &[&[u8]; 4] = &[
+            b"lp_mint",
+            token_a_mint_key.as_ref(),
+            token_b_mint_key.as_ref(),
+            &[bumps.lp_mint],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        create_pda_account(
+            &self.signer.to_account_info(),
+            &lp_mint_info,
+            &self.system_program.to_account_info(),
+            spl_token::state::Mint::LEN,
+            &self.token_program.key(),
+            signer_seeds,
+        )?;
+
+        let initialize_lp_mint_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            InitializeMint2 { mint: lp_mint_info },
+        );
+
+        let lp_decimals = self.token_a_mint.decimals.max(self.token_b_mint.decimals);
+        let pool_authority = self.pool_authority.key();
+        initialize_mint2(
+            initialize_lp_mint_ctx,
+            lp_decimals,
+            &pool_authority,
+            Some(&pool_authority),
+        )?;
+
+        Ok(lp_decimals)
+    }
+
+    pub fn create_lp_metadata(&mut self, bumps: &InitializeBumps) -> Result<()> {
+        let token_a_mint_key = self.token_a_mint.key();
+        let token_b_mint_key = self.token_b_mint.key();
+
+        let seeds: &[&[u8]; 4] = &[
+            b"authority",
+            token_a_mint_key.as_ref(),
+            token_b_mint_key.as_ref(),
+            &[bumps.pool_authority],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        // the creator can set a custom name, symbol and URI with `update_lp_metadata`
+        let symbol_a = self.token_a_metadata.as_ref().map(|m| m.symbol.as_str());
+        let symbol_b = self.token_b_metadata.as_ref().map(|m| m.symbol.as_str());
+        let (name, symbol) = lp_metadata_names(
+            &pair_symbol(symbol_a, &token_a_mint_key),
+            &pair_symbol(symbol_b, &token_b_mint_key),
+        );
+        let data = DataV2 {
+            name,
+            symbol,
+            uri: String::new(),
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        };
+
+        // a pair re-created after `close_pool` already has metadata on its lp_mint
+        if self.lp_metadata.data_is_empty() {
+            let create_metadata_ctx = CpiContext::new_with_signer(
+                self.metadata_program.to_account_info(),
+                CreateMetadataAccountsV3 {
+                    metadata: self.lp_metadata.to_account_info(),
+                    mint: self.lp_mint.to_account_info(),
+                    mint_authority: self.pool_authority.to_account_info(),
+                    payer: self.signer.to_account_info(),
+                    update_authority: self.pool_authority.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                    rent: self.rent.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            create_metadata_accounts_v3(create_metadata_ctx, data, true, true, None)
+        } else {
+            let update_metadata_ctx = CpiContext::new_with_signer(
+                self.metadata_program.to_account_info(),
+                UpdateMetadataAccountsV2 {
+                    metadata: self.lp_metadata.to_account_info(),
+                    update_authority: self.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            );
+
+            update_metadata_accounts_v2(update_metadata_ctx, None, Some(data), None, None)
+        }
+    }
+}
+
+// A token's metadata symbol, or the first characters of its mint address when
+// it has none
+fn pair_symbol(symbol: Option<&str>, mint: &Pubkey) -> String {
+    // the metadata program pads stored strings with null bytes
+    let symbol = symbol.unwrap_or_default().trim_end_matches('\0');
+    if symbol.is_empty() {
+        mint.to_string()[..4].to_string()
+    } else {
+        symbol.to_string()
+    }
+}
+
+// name - "A-B LP", symbol - "A-B-LP", or "A-B" cut to fit when that is too long
+// for the metadata program
+fn lp_metadata_names(symbol_a: &str, symbol_b: &str) -> (String, String) {
+    let name = truncate(format!("{symbol_a}-{symbol_b} LP"), MAX_NAME_LENGTH);
+    let symbol = format!("{symbol_a}-{symbol_b}-LP");
+    if symbol.len() <= MAX_SYMBOL_LENGTH {
+        return (name, symbol);
+    }
+
+    let symbol = truncate(format!("{symbol_a}-{symbol_b}"), MAX_SYMBOL_LENGTH);
+    (name, symbol)
+}
+
+fn truncate(mut value: String, max_len: usize) -> String {
+    while value.len() > max_len {
+        value.pop();
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lp_names_fit_the_metadata_limits() {
+        assert_eq!(
+            lp_metadata_names("SOL", "BONK"),
+            ("SOL-BONK LP".to_string(), "SOL-BONK".to_string())
+        );
+        assert_eq!(
+            lp_metadata_names("A", "B"),
+            ("A-B LP".to_string(), "A-B-LP".to_string())
+        );
+
+        let long = "X".repeat(MAX_NAME_LENGTH);
+        let (name, symbol) = lp_metadata_names(&long, "B");
+        assert_eq!(name.len(), MAX_NAME_LENGTH);
+        assert_eq!(symbol.len(), MAX_SYMBOL_LENGTH);
+    }
 }
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    program::Amm,
+    states::{AMMError, Config, BPS_DENOMINATOR},
+};
+
+// Created once by the program upgrade authority, who becomes the config authority
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config"],
+        bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, Amm>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(signer.key())
+            @ AMMError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> InitializeConfig<'info> {
+    pub fn initialize_config(
+        &mut self,
+        max_referral_fee_bps: u16,
+        bumps: &InitializeConfigBumps,
+    ) -> Result<()> {
+        require!(
+            max_referral_fee_bps as u64 <= BPS_DENOMINATOR,
+            AMMError::ReferralFeeTooHigh
+        );
+
+        self.config.set_inner(Config {
+            authority: self.signer.key(),
+            max_referral_fee_bps,
+            bump: bumps.config,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::states::{AMMError, Farm, AMM};
+
+#[derive(Accounts)]
+pub struct InitializeFarm<'info> {
+    #[account(
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        has_one = creator @ AMMError::Unauthorized,
+        has_one = lp_mint,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Farm::INIT_SPACE,
+        seeds = [b"farm", amm.key().as_ref()],
+        bump,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    // staked LP tokens are held here
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"farm_lp_vault", farm.key().as_ref()],
+        bump,
+        token::mint = lp_mint,
+        token::authority = farm
+    )]
+    pub lp_vault: Box<Account<'info, TokenAccount>>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+}
+
+impl<'info> InitializeFarm<'info> {
+    pub fn initialize_farm(&mut self, bumps: &InitializeFarmBumps) -> Result<()> {
+        self.farm.set_inner(Farm {
+            amm: self.amm.key(),
+            lp_mint: self.lp_mint.key(),
+            lp_vault: self.lp_vault.key(),
+            authority: self.creator.key(),
+            total_staked: 0,
+            reward_count: 0,
+            rewards: Default::default(),
+            bump: bumps.farm,
+        });
+
+        Ok(())
+    }
+}
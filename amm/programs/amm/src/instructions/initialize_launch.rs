@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+use crate::states::{AMMError, AMM, AMM_VERSION, LAUNCH_ACTIVE, LAUNCH_NONE};
+
+// Opens a launch pool for a freshly created pair. The launch pool is an AMM
+// account of its own at [b"launch", a, b] with its own reserves. The creator
+// seeds the base (launched) token one-sided and the quote side starts with
+// virtual reserves only, so the token has a non-zero price before anyone has
+// bought it. Trades go through `swap_launch` until `graduate` moves the real
+// reserves into the pair's standard pool.
+// The standard pool refuses deposits while the launch is active. Send this in
+// the same transaction as `initialize`, so nobody can seed the standard pool
+// before it.
+
+#[derive(Accounts)]
+pub struct InitializeLaunch<'info> {
+    #[account(
+        mut,
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        has_one = creator @ AMMError::Unauthorized,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + std::mem::size_of::<AMM>(),
+        seeds=[b"launch", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub launch: AccountLoader<'info, AMM>,
+
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"launch_reserve_a", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = pool_authority
+    )]
+    pub launch_reserve_a: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"launch_reserve_b", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = pool_authority
+    )]
+    pub launch_reserve_b: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: pool authority over token reserves and lp mint
+    #[account(
+        seeds=[b"authority", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::authority = creator
+    )]
+    pub creator_base_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+}
+
+impl<'info> InitializeLaunch<'info> {
+    pub fn initialize_launch(
+        &mut self,
+        base_amount: u64,
+        virtual_quote_reserve: u64,
+        graduation_quote_reserve: u64,
+        base_is_a: bool,
+    ) -> Result<()> {
+        require!(
+            base_amount > 0 && virtual_quote_reserve > 0 && graduation_quote_reserve > 0,
+            AMMError::InvalidLaunch
+        );
+
+        let mut amm = self.amm.load_mut()?;
+        require!(
+            amm.launch_state == LAUNCH_NONE && amm.lp_supply == 0,
+            AMMError::InvalidLaunch
+        );
+        amm.launch_state = LAUNCH_ACTIVE;
+
+        let clock = Clock::get()?;
+        let mut launch = self.launch.load_init()?;
+        launch.version = AMM_VERSION;
+        launch.token_a_mint = self.token_a_mint.key();
+        launch.token_b_mint = self.token_b_mint.key();
+        launch.reserve_a = self.launch_reserve_a.key();
+        launch.reserve_b = self.launch_reserve_b.key();
+        launch.pool_authority = self.pool_authority.key();
+        launch.creator = self.creator.key();
+        launch.created_slot = clock.slot;
+        launch.created_at = clock.unix_timestamp;
+        // launch trades pay the standard pool's base fee, which graduates
+        // with the reserves
+        launch.min_fee_bps = amm.min_fee_bps;
+        launch.max_fee_bps = amm.min_fee_bps;
+
+        let (base_mint, base_reserve) = if base_is_a {
+            launch.virtual_reserve_b = virtual_quote_reserve;
+            (self.token_a_mint.key(), &self.launch_reserve_a)
+        } else {
+            launch.virtual_reserve_a = virtual_quote_reserve;
+            (self.token_b_mint.key(), &self.launch_reserve_b)
+        };
+        require_keys_eq!(
+            self.creator_base_account.mint,
+            base_mint,
+            AMMError::InvalidLaunch
+        );
+
+        launch.graduation_quote_reserve = graduation_quote_reserve;
+        launch.launch_state = LAUNCH_ACTIVE;
+        drop(launch);
+        drop(amm);
+
+        let transfer_to_reserve_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.creator_base_account.to_account_info(),
+                to: base_reserve.to_account_info(),
+                authority: self.creator.to_account_info(),
+            },
+        );
+
+        transfer(transfer_to_reserve_ctx, base_amount)
+    }
+}
@@ -0,0 +1,160 @@
+use anchor_lang::{prelude::*, solana_program::program_pack::Pack};
+use anchor_spl::token::{
+    initialize_account3, initialize_mint2, spl_token, InitializeAccount3, InitializeMint2, Mint,
+    Token,
+};
+
+use crate::{
+    instructions::{multi_pool::multi_pool_seed, utils::create_pda_account},
+    states::{AMMError, MultiPool, MAX_FEE_BPS, MULTI_POOL_MAX_TOKENS, MULTI_POOL_MIN_TOKENS},
+};
+
+// remaining_accounts: [mint_0 .. mint_n-1, reserve_0 .. reserve_n-1]
+// The lp_mint and reserves are created here rather than through `init`
+// constraints since their number and the LP decimals depend on the mints.
+
+#[derive(Accounts)]
+#[instruction(mints: Vec<Pubkey>)]
+pub struct InitializeMultiPool<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + MultiPool::INIT_SPACE,
+        seeds = [b"multi_pool", multi_pool_seed(&mints).as_ref()],
+        bump,
+    )]
+    pub multi_pool: Box<Account<'info, MultiPool>>,
+
+    /// CHECK: created and initialized by the handler
+    #[account(
+        mut,
+        seeds = [b"multi_lp_mint", multi_pool.key().as_ref()],
+        bump,
+    )]
+    pub lp_mint: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> InitializeMultiPool<'info> {
+    pub fn initialize_multi_pool(
+        &mut self,
+        mints: Vec<Pubkey>,
+        fee_bps: u16,
+        remaining_accounts: &[AccountInfo<'info>],
+        bumps: &InitializeMultiPoolBumps,
+    ) -> Result<()> {
+        let token_count = mints.len();
+        require!(
+            (MULTI_POOL_MIN_TOKENS..=MULTI_POOL_MAX_TOKENS).contains(&token_count),
+            AMMError::InvalidTokenCount
+        );
+        require!(
+            mints.windows(2).all(|pair| pair[0] < pair[1]),
+            AMMError::UnsortedMints
+        );
+        require!(fee_bps <= MAX_FEE_BPS, AMMError::InvalidFee);
+        require!(
+            remaining_accounts.len() == 2 * token_count,
+            AMMError::InvalidPoolAccounts
+        );
+
+        let (mint_accounts, reserve_accounts) = remaining_accounts.split_at(token_count);
+        let pool_key = self.multi_pool.key();
+
+        let mut decimals = [0u8; MULTI_POOL_MAX_TOKENS];
+        let mut reserves = [Pubkey::default(); MULTI_POOL_MAX_TOKENS];
+
+        let accounts = mint_accounts.iter().zip(reserve_accounts);
+        for (i, (mint_info, reserve_info)) in accounts.enumerate() {
+            require_keys_eq!(mint_info.key(), mints[i], AMMError::InvalidPoolAccounts);
+            let mint = Mint::try_deserialize(&mut &mint_info.try_borrow_data()?[..])?;
+            decimals[i] = mint.decimals;
+
+            let (reserve_key, reserve_bump) = Pubkey::find_program_address(
+                &[b"multi_reserve", pool_key.as_ref(), mints[i].as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(
+                reserve_info.key(),
+                reserve_key,
+                AMMError::InvalidPoolAccounts
+            );
+
+            let seeds: &[&[u8]; 4] = &[
+                b"multi_reserve",
+                pool_key.as_ref(),
+                mints[i].as_ref(),
+                &[reserve_bump],
+            ];
+            let signer_seeds = &[&seeds[..]];
+
+            create_pda_account(
+                &self.signer.to_account_info(),
+                reserve_info,
+                &self.system_program.to_account_info(),
+                spl_token::state::Account::LEN,
+                &self.token_program.key(),
+                signer_seeds,
+            )?;
+
+            let initialize_reserve_ctx = CpiContext::new(
+                self.token_program.to_account_info(),
+                InitializeAccount3 {
+                    account: reserve_info.clone(),
+                    mint: mint_info.clone(),
+                    authority: self.multi_pool.to_account_info(),
+                },
+            );
+
+            initialize_account3(initialize_reserve_ctx)?;
+            reserves[i] = reserve_key;
+        }
+
+        let lp_decimals = decimals[..token_count].iter().copied().max().unwrap_or(0);
+
+        let seeds: &[&[u8]; 3] = &[b"multi_lp_mint", pool_key.as_ref(), &[bumps.lp_mint]];
+        let signer_seeds = &[&seeds[..]];
+
+        create_pda_account(
+            &self.signer.to_account_info(),
+            &self.lp_mint.to_account_info(),
+            &self.system_program.to_account_info(),
+            spl_token::state::Mint::LEN,
+            &self.token_program.key(),
+            signer_seeds,
+        )?;
+
+        let initialize_lp_mint_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            InitializeMint2 {
+                mint: self.lp_mint.to_account_info(),
+            },
+        );
+
+        initialize_mint2(initialize_lp_mint_ctx, lp_decimals, &pool_key, None)?;
+
+        let mut pool_mints = [Pubkey::default(); MULTI_POOL_MAX_TOKENS];
+        pool_mints[..token_count].copy_from_slice(&mints);
+
+        self.multi_pool.set_inner(MultiPool {
+            mints_hash: multi_pool_seed(&mints),
+            creator: self.signer.key(),
+            lp_mint: self.lp_mint.key(),
+            lp_supply: 0,
+            lp_decimals,
+            fee_bps,
+            token_count: token_count as u8,
+            mints: pool_mints,
+            reserves,
+            decimals,
+            bump: bumps.multi_pool,
+            lp_mint_bump: bumps.lp_mint,
+        });
+
+        Ok(())
+    }
+}
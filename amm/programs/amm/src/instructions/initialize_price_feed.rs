@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::states::PriceFeed;
+
+#[derive(Accounts)]
+#[instruction(feed_id: u64)]
+pub struct InitializePriceFeed<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PriceFeed::INIT_SPACE,
+        seeds = [b"price_feed", authority.key().as_ref(), &feed_id.to_le_bytes()],
+        bump,
+    )]
+    pub price_feed: Box<Account<'info, PriceFeed>>,
+
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+impl<'info> InitializePriceFeed<'info> {
+    pub fn initialize_price_feed(
+        &mut self,
+        feed_id: u64,
+        expo: i32,
+        bumps: &InitializePriceFeedBumps,
+    ) -> Result<()> {
+        self.price_feed.set_inner(PriceFeed {
+            authority: self.authority.key(),
+            feed_id,
+            price: 0,
+            expo,
+            publish_time: 0,
+            bump: bumps.price_feed,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+use crate::{instructions::utils::to_u64, states::AMMError};
+
+// Base tokens kept when a launch pool graduates so that the real reserves
+// alone trade at the price the launch pool quoted off real + virtual
+// reserves, the rest is burned
+// baseKept = baseReserve X quoteReserve / (quoteReserve + virtualQuote)
+pub fn graduated_base_reserve(
+    base_reserve: u64,
+    quote_reserve: u64,
+    virtual_quote: u64,
+) -> Result<u64> {
+    let base_kept = (base_reserve as u128)
+        .checked_mul(quote_reserve as u128)
+        .and_then(|v| v.checked_div(quote_reserve as u128 + virtual_quote as u128))
+        .ok_or(AMMError::ArithmeticOverflow)?;
+
+    to_u64(base_kept)
+}
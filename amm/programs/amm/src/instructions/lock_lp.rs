@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+use crate::states::{AMMError, LpLock, AMM};
+
+#[derive(Accounts)]
+#[instruction(lock_id: u64)]
+pub struct LockLp<'info> {
+    #[account(
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        has_one = lp_mint,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + LpLock::INIT_SPACE,
+        seeds = [
+            b"lp_lock",
+            amm.key().as_ref(),
+            signer.key().as_ref(),
+            &lock_id.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub lock: Box<Account<'info, LpLock>>,
+
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"lp_lock_vault", lock.key().as_ref()],
+        bump,
+        token::mint = lp_mint,
+        token::authority = lock
+    )]
+    pub lock_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = signer
+    )]
+    pub token_lp_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: any wallet can be the beneficiary of the unlocked LP tokens
+    pub beneficiary: UncheckedAccount<'info>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> LockLp<'info> {
+    pub fn lock_lp(
+        &mut self,
+        lock_id: u64,
+        amount: u64,
+        unlock_timestamp: i64,
+        vesting_duration: u64,
+        bumps: &LockLpBumps,
+    ) -> Result<()> {
+        require!(amount > 0, AMMError::InvalidQuantity);
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(unlock_timestamp > now, AMMError::InvalidUnlockTime);
+
+        let vesting_end_ts = i64::try_from(vesting_duration)
+            .ok()
+            .and_then(|duration| unlock_timestamp.checked_add(duration))
+            .ok_or(AMMError::ArithmeticOverflow)?;
+
+        self.lock.set_inner(LpLock {
+            amm: self.amm.key(),
+            lp_mint: self.lp_mint.key(),
+            locker: self.signer.key(),
+            beneficiary: self.beneficiary.key(),
+            lock_id,
+            amount,
+            withdrawn: 0,
+            locked_at: now,
+            unlock_ts: unlock_timestamp,
+            vesting_end_ts,
+            bump: bumps.lock,
+        });
+
+        let lock_lp_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.token_lp_account.to_account_info(),
+                to: self.lock_vault.to_account_info(),
+                authority: self.signer.to_account_info(),
+            },
+        );
+
+        transfer(lock_lp_ctx, amount)
+    }
+}
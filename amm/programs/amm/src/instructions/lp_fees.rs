@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    instructions::utils::{mul_q64, to_u64},
+    states::{AMMError, LpFeeCheckpoint, AMM},
+};
+
+// LP FEES
+// Swap fees stay in the reserves and accrue_lp_fee adds them to the pool's
+// fee_growth_a/b, per LP token. LP balances and positions checkpoint that
+// growth and are owed their shares X the growth since.
+
+// Adds the fee a trade left in the reserve of token A (or B) to that token's
+// per-LP-token accumulator
+// feeGrowth += fee X 2^64 / lpSupply
+pub fn accrue_lp_fee(amm: &mut AMM, fee: u64, fee_in_a: bool) {
+    if amm.lp_supply == 0 {
+        return;
+    }
+
+    let growth = ((fee as u128) << 64) / amm.lp_supply as u128;
+    if fee_in_a {
+        amm.fee_growth_a = amm.fee_growth_a.wrapping_add(growth);
+    } else {
+        amm.fee_growth_b = amm.fee_growth_b.wrapping_add(growth);
+    }
+}
+
+// Fees earned by `shares` LP tokens (or position shares) while the pool's fee
+// growth moved from `checkpoint` to `growth`
+// fee = shares X (growth - checkpoint) / 2^64
+pub fn fee_growth_fees(shares: u64, growth: u128, checkpoint: u128) -> Result<u64> {
+    let growth = growth.wrapping_sub(checkpoint);
+    to_u64(mul_q64(shares as u128, growth).ok_or(AMMError::ArithmeticOverflow)?)
+}
+
+// Fees earned by the checkpoint's LP tokens since the checkpoint, counting no
+// more than the `wallet_balance` the owner still holds
+// fee = min(lpBalance, walletBalance) X (feeGrowth - checkpointGrowth) / 2^64
+pub fn pending_lp_fees(
+    amm: &AMM,
+    checkpoint: &LpFeeCheckpoint,
+    wallet_balance: u64,
+) -> Result<(u64, u64)> {
+    let lp_balance = checkpoint.lp_balance.min(wallet_balance);
+    Ok((
+        fee_growth_fees(lp_balance, amm.fee_growth_a, checkpoint.fee_growth_a)?,
+        fee_growth_fees(lp_balance, amm.fee_growth_b, checkpoint.fee_growth_b)?,
+    ))
+}
+
+// Moves the fees earned since the checkpoint into earned_a/b, caps lp_balance
+// at the `wallet_balance` and moves the checkpoint up to the pool's current
+// growth. Must run before the wallet's LP balance changes.
+pub fn settle_lp_fees(
+    amm: &AMM,
+    checkpoint: &mut LpFeeCheckpoint,
+    wallet_balance: u64,
+) -> Result<()> {
+    let (fees_a, fees_b) = pending_lp_fees(amm, checkpoint, wallet_balance)?;
+    checkpoint.earned_a = checkpoint
+        .earned_a
+        .checked_add(fees_a)
+        .ok_or(AMMError::ArithmeticOverflow)?;
+    checkpoint.earned_b = checkpoint
+        .earned_b
+        .checked_add(fees_b)
+        .ok_or(AMMError::ArithmeticOverflow)?;
+
+    checkpoint.lp_balance = checkpoint.lp_balance.min(wallet_balance);
+    checkpoint.fee_growth_a = amm.fee_growth_a;
+    checkpoint.fee_growth_b = amm.fee_growth_b;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_growth_is_per_lp_token() {
+        let mut amm: AMM = bytemuck::Zeroable::zeroed();
+
+        // nobody to accrue to
+        accrue_lp_fee(&mut amm, 1_000, true);
+        assert_eq!(amm.fee_growth_a, 0);
+
+        amm.lp_supply = 4_000;
+        accrue_lp_fee(&mut amm, 1_000, true);
+        accrue_lp_fee(&mut amm, 3_000, false);
+        assert_eq!(amm.fee_growth_a, 1 << 62);
+        assert_eq!(amm.fee_growth_b, 3 << 62);
+    }
+}
@@ -0,0 +1,105 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+    Discriminator,
+};
+use anchor_spl::token::Mint;
+
+use crate::{
+    program::Amm,
+    states::{AMMError, AMM, AMM_VERSION},
+};
+
+// Pools created before `version` existed are smaller than the current layout,
+// so the account is taken unchecked and grown to the current size before it is
+// read as an AMM. Gated on the program upgrade authority since legacy pools
+// have no creator recorded.
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    /// CHECK: PDA validated by seeds - owner and discriminator checked before the realloc
+    #[account(
+        mut,
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub amm: UncheckedAccount<'info>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        seeds = [b"lp_mint", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, Amm>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(signer.key())
+            @ AMMError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> MigratePool<'info> {
+    pub fn migrate_pool(&mut self) -> Result<()> {
+        let amm_info = self.amm.to_account_info();
+        require_keys_eq!(
+            *amm_info.owner,
+            crate::ID,
+            ErrorCode::AccountOwnedByWrongProgram
+        );
+        require!(
+            amm_info.try_borrow_data()?.starts_with(&AMM::DISCRIMINATOR),
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+
+        let space = 8 + std::mem::size_of::<AMM>();
+        if amm_info.data_len() < space {
+            let rent = Rent::get()?
+                .minimum_balance(space)
+                .saturating_sub(amm_info.lamports());
+
+            if rent > 0 {
+                let pay_rent_ctx = CpiContext::new(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.signer.to_account_info(),
+                        to: amm_info.clone(),
+                    },
+                );
+
+                transfer(pay_rent_ctx, rent)?;
+            }
+
+            amm_info.realloc(space, true)?;
+        }
+
+        let mut data = amm_info.try_borrow_mut_data()?;
+        let amm: &mut AMM = bytemuck::from_bytes_mut(&mut data[8..space]);
+        require!(amm.version < AMM_VERSION, AMMError::AlreadyMigrated);
+
+        // v0 -> v1
+        // creation slot and timestamp are unknown and stay 0
+        if amm.version < 1 && amm.creator == Pubkey::default() {
+            amm.creator = self.signer.key();
+        }
+
+        // v1 -> v2
+        // earlier pools always created a 6-decimal lp_mint, read it from the mint
+        if amm.version < 2 {
+            amm.lp_decimals = self.lp_mint.decimals;
+        }
+        amm.version = AMM_VERSION;
+
+        Ok(())
+    }
+}
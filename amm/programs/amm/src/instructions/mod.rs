@@ -1,9 +1,124 @@
+pub mod add_allowlist_entry;
+pub mod add_farm_reward;
+pub mod allowlist;
+pub mod batch;
+pub mod cancel_intent;
+pub mod cancel_limit_order;
+pub mod circuit_breaker;
+pub mod claim_intent;
+pub mod close_dca;
+pub mod close_pool;
+pub mod create_lp_fee_checkpoint;
 pub mod deposit;
+pub mod deposit_multi;
+pub mod deposit_multi_single;
+pub mod deposit_position;
+pub mod deposit_single;
+pub mod dynamic_fee;
+pub mod execute_dca;
+pub mod farm;
+pub mod fill_orders;
+pub mod fund_farm_reward;
+pub mod graduate;
+pub mod harvest;
 pub mod initialize;
+pub mod initialize_config;
+pub mod initialize_farm;
+pub mod initialize_launch;
+pub mod initialize_multi_pool;
+pub mod initialize_price_feed;
+pub mod launch;
+pub mod lock_lp;
+pub mod lp_fees;
+pub mod migrate_pool;
+pub mod multi_pool;
+pub mod native_sol;
+pub mod open_dca;
+pub mod oracle;
+pub mod place_limit_order;
+pub mod position;
+pub mod quote_earned_fees;
+pub mod quote_position_fees;
+pub mod register_referrer;
+pub mod remove_allowlist_entry;
+pub mod remove_referrer;
+pub mod set_allowlist_authority;
+pub mod set_batch_mode;
+pub mod set_circuit_breaker;
+pub mod set_fee_config;
+pub mod set_pool_oracle;
+pub mod set_referral_fee;
+pub mod settle_batch;
+pub mod stake_lp;
+pub mod submit_intent;
 pub mod swap;
+pub mod swap_launch;
+pub mod swap_multi;
+pub mod unlock_lp;
+pub mod unstake_lp;
+pub mod update_config;
+pub mod update_lp_metadata;
+pub mod update_price_feed;
+pub mod utils;
+pub mod vesting;
 pub mod withdraw;
+pub mod withdraw_multi;
+pub mod withdraw_multi_single;
+pub mod withdraw_position;
+pub mod withdraw_single;
 
+pub use add_allowlist_entry::*;
+pub use add_farm_reward::*;
+pub use cancel_intent::*;
+pub use cancel_limit_order::*;
+pub use claim_intent::*;
+pub use close_dca::*;
+pub use close_pool::*;
+pub use create_lp_fee_checkpoint::*;
 pub use deposit::*;
+pub use deposit_multi::*;
+pub use deposit_multi_single::*;
+pub use deposit_position::*;
+pub use deposit_single::*;
+pub use execute_dca::*;
+pub use fill_orders::*;
+pub use fund_farm_reward::*;
+pub use graduate::*;
+pub use harvest::*;
 pub use initialize::*;
+pub use initialize_config::*;
+pub use initialize_farm::*;
+pub use initialize_launch::*;
+pub use initialize_multi_pool::*;
+pub use initialize_price_feed::*;
+pub use lock_lp::*;
+pub use migrate_pool::*;
+pub use open_dca::*;
+pub use place_limit_order::*;
+pub use quote_earned_fees::*;
+pub use quote_position_fees::*;
+pub use register_referrer::*;
+pub use remove_allowlist_entry::*;
+pub use remove_referrer::*;
+pub use set_allowlist_authority::*;
+pub use set_batch_mode::*;
+pub use set_circuit_breaker::*;
+pub use set_fee_config::*;
+pub use set_pool_oracle::*;
+pub use set_referral_fee::*;
+pub use settle_batch::*;
+pub use stake_lp::*;
+pub use submit_intent::*;
 pub use swap::*;
+pub use swap_launch::*;
+pub use swap_multi::*;
+pub use unlock_lp::*;
+pub use unstake_lp::*;
+pub use update_config::*;
+pub use update_lp_metadata::*;
+pub use update_price_feed::*;
 pub use withdraw::*;
+pub use withdraw_multi::*;
+pub use withdraw_multi_single::*;
+pub use withdraw_position::*;
+pub use withdraw_single::*;
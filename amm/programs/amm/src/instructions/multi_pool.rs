@@ -0,0 +1,215 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+use anchor_spl::token::{transfer, TokenAccount, Transfer};
+
+use crate::{
+    instructions::utils::{mul_q64, to_u64, Q64},
+    states::{AMMError, MultiPool, BPS_DENOMINATOR},
+};
+
+// Multi-asset pool instructions take their reserves (and the user's token
+// accounts) as remaining_accounts, since the number of tokens varies per pool.
+// Reserves are PDAs owned by the pool account, which also signs for them:
+// reserve - [b"multi_reserve", pool, mint]
+// lp_mint - [b"multi_lp_mint", pool]
+
+pub fn multi_pool_seed(mints: &[Pubkey]) -> [u8; 32] {
+    let seeds: Vec<&[u8]> = mints.iter().map(|mint| mint.as_ref()).collect();
+    hashv(&seeds).to_bytes()
+}
+
+// Checks that `reserve` is the pool's reserve for token `index` and returns its balance
+pub fn reserve_amount(pool: &MultiPool, index: usize, reserve: &AccountInfo) -> Result<u64> {
+    require!(
+        index < pool.token_count as usize && reserve.key() == pool.reserves[index],
+        AMMError::InvalidPoolAccounts
+    );
+
+    let reserve = TokenAccount::try_deserialize(&mut &reserve.try_borrow_data()?[..])?;
+    Ok(reserve.amount)
+}
+
+pub fn transfer_to_reserve<'info>(
+    token_program: AccountInfo<'info>,
+    signer: AccountInfo<'info>,
+    user_account: AccountInfo<'info>,
+    reserve: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let transfer_to_reserve_ctx = CpiContext::new(
+        token_program,
+        Transfer {
+            from: user_account,
+            to: reserve,
+            authority: signer,
+        },
+    );
+
+    transfer(transfer_to_reserve_ctx, amount)
+}
+
+pub fn transfer_from_reserve<'info>(
+    token_program: AccountInfo<'info>,
+    pool: &Account<'info, MultiPool>,
+    reserve: AccountInfo<'info>,
+    user_account: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let seeds: &[&[u8]; 3] = &[b"multi_pool", pool.mints_hash.as_ref(), &[pool.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    let transfer_to_user_ctx = CpiContext::new_with_signer(
+        token_program,
+        Transfer {
+            from: reserve,
+            to: user_account,
+            authority: pool.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    transfer(transfer_to_user_ctx, amount)
+}
+
+// Multi-asset pools keep prod(reserves) constant with equal weights, so a swap
+// between two of their tokens prices exactly like a pair pool. Single-sided
+// deposits and withdrawals need n-th powers and roots of reserve ratios, which
+// are done in Q64.64 fixed point.
+
+// mul_q64 rounded up instead of down - only the lowest partial product has a
+// fractional part
+fn mul_q64_ceil(a: u128, b: u128) -> Option<u128> {
+    let remainder = ((a & (Q64 - 1)) * (b & (Q64 - 1))) & (Q64 - 1);
+    mul_q64(a, b)?.checked_add((remainder != 0) as u128)
+}
+
+pub fn pow_q64(base: u128, exponent: u32) -> Option<u128> {
+    (1..exponent).try_fold(base, |acc, _| mul_q64(acc, base))
+}
+
+fn pow_q64_ceil(base: u128, exponent: u32) -> Option<u128> {
+    (1..exponent).try_fold(base, |acc, _| mul_q64_ceil(acc, base))
+}
+
+// largest y with y^n <= x, for x >= 1.0
+pub fn nth_root_q64(x: u128, n: u32) -> u128 {
+    let mut left = Q64;
+    let mut right = x;
+
+    while left < right {
+        let mid = left + (right - left).div_ceil(2);
+        match pow_q64(mid, n) {
+            Some(value) if value <= x => left = mid,
+            _ => right = mid - 1,
+        }
+    }
+    left
+}
+
+// the swap fee applies to the (n - 1) / n of a single-sided amount that is
+// implicitly traded into the other tokens, rounded up like amount_after_fee
+fn multi_single_fee(amount: u64, token_count: u8, fee_bps: u16) -> u64 {
+    let n = token_count as u128;
+    let fee = (amount as u128 * fee_bps as u128 * (n - 1)).div_ceil(BPS_DENOMINATOR as u128 * n);
+    fee as u64
+}
+
+pub fn initial_multi_lp_tokens(
+    quantities: &[u64],
+    decimals: &[u8],
+    lp_decimals: u8,
+) -> Result<u64> {
+    // LP[minted] = sum of the deposits in whole tokens, scaled to lp_decimals
+    // lp_decimals is the largest of the mints' decimals so every scale is >= 1
+    let mut lp_tokens: u128 = 0;
+    for (&quantity, &token_decimals) in quantities.iter().zip(decimals) {
+        let scale = 10u128.pow((lp_decimals - token_decimals) as u32);
+        lp_tokens = (quantity as u128)
+            .checked_mul(scale)
+            .and_then(|scaled| lp_tokens.checked_add(scaled))
+            .ok_or(AMMError::ArithmeticOverflow)?;
+    }
+
+    to_u64(lp_tokens)
+}
+
+// amount = ceil(shares X reserve / totalSupply) - deposits round in the pool's favour
+pub fn proportional_deposit_amount(lp_tokens: u64, reserve: u64, lp_supply: u64) -> Result<u64> {
+    let amount = (lp_tokens as u128)
+        .checked_mul(reserve as u128)
+        .ok_or(AMMError::ArithmeticOverflow)?
+        .div_ceil(lp_supply as u128);
+
+    to_u64(amount)
+}
+
+pub fn multi_single_deposit_lp(
+    amount_in: u64,
+    reserve: u64,
+    lp_supply: u64,
+    token_count: u8,
+    fee_bps: u16,
+) -> Result<u64> {
+    // LP[minted] = LP[total] X ((1 + a / r)^(1/n) - 1)
+    let amount = amount_in - multi_single_fee(amount_in, token_count, fee_bps);
+    let reserve_after = reserve
+        .checked_add(amount)
+        .ok_or(AMMError::ArithmeticOverflow)?;
+
+    // r + a < 2^64 so the shifted ratio fits in u128
+    let ratio = ((reserve_after as u128) << 64) / reserve as u128;
+    let growth = nth_root_q64(ratio, token_count as u32) - Q64;
+
+    let lp_tokens = mul_q64(growth, lp_supply as u128).ok_or(AMMError::ArithmeticOverflow)?;
+    to_u64(lp_tokens)
+}
+
+pub fn multi_single_withdraw_amount(
+    lp_token_quantity: u64,
+    reserve: u64,
+    lp_supply: u64,
+    token_count: u8,
+    fee_bps: u16,
+) -> Result<u64> {
+    // amount = r X (1 - ((LP[total] - LP) / LP[total])^n)
+    let remaining = lp_supply
+        .checked_sub(lp_token_quantity)
+        .ok_or(AMMError::ArithmeticOverflow)?;
+
+    // the share of the reserve that stays in the pool is rounded up, so the
+    // amount paid out rounds down like the other withdrawal paths
+    // the ratio is at most 1.0, so its powers never overflow
+    let ratio = ((remaining as u128) << 64).div_ceil(lp_supply as u128);
+    let kept = pow_q64_ceil(ratio, token_count as u32)
+        .ok_or(AMMError::ArithmeticOverflow)?
+        .min(Q64);
+
+    let amount = to_u64(mul_q64(Q64 - kept, reserve as u128).ok_or(AMMError::ArithmeticOverflow)?)?;
+    Ok(amount - multi_single_fee(amount, token_count, fee_bps))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multi_single_withdrawals_round_down() {
+        let (reserve, lp_supply) = (u64::MAX as u128 - 12_345, 997u128);
+
+        for n in 2..=4u32 {
+            for lp in 1..=lp_supply {
+                let amount = multi_single_withdraw_amount(
+                    lp as u64,
+                    reserve as u64,
+                    lp_supply as u64,
+                    n as u8,
+                    0,
+                )
+                .unwrap();
+
+                // amount <= r X (1 - ((S - lp) / S)^n), compared exactly
+                let exact = reserve * (lp_supply.pow(n) - (lp_supply - lp).pow(n));
+                assert!(amount as u128 * lp_supply.pow(n) <= exact);
+            }
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Transfer},
+};
+use anchor_spl::token::{
+    self, close_account, initialize_account3, spl_token::native_mint, sync_native, CloseAccount,
+    InitializeAccount3, SyncNative, TokenAccount,
+};
+
+use crate::{instructions::utils::create_pda_account, states::AMMError};
+
+// The `_sol` instruction variants wrap the lamports they take into a temporary
+// wSOL account at [b"wsol", signer] and pay the pool from it. wSOL they pay
+// out lands in the signer's associated token account as usual, and only what
+// the instruction added there is unwrapped, through the same temporary account.
+// The temporary account is created and closed within the instruction, so wSOL
+// the signer already held is never touched.
+
+// Returns true if token A of the pair is wrapped SOL
+pub fn is_native_a(token_a_mint: &Pubkey, token_b_mint: &Pubkey) -> Result<bool> {
+    if *token_a_mint == native_mint::ID {
+        Ok(true)
+    } else if *token_b_mint == native_mint::ID {
+        Ok(false)
+    } else {
+        err!(AMMError::NotNativeMint)
+    }
+}
+
+// The account a `_sol` variant pays `user_account`'s side from - the temporary
+// account if the input was wrapped into it, `user_account` otherwise
+pub fn input_account<'info>(
+    user_account: &Account<'info, TokenAccount>,
+    temp_account: Option<&UncheckedAccount<'info>>,
+) -> AccountInfo<'info> {
+    match temp_account {
+        Some(temp_account)
+            if user_account.mint == native_mint::ID && !temp_account.data_is_empty() =>
+        {
+            temp_account.to_account_info()
+        }
+        _ => user_account.to_account_info(),
+    }
+}
+
+// Creates the temporary account holding `lamports` as wSOL
+pub fn wrap_sol<'info>(
+    system_program: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer: AccountInfo<'info>,
+    temp: (AccountInfo<'info>, u8),
+    native_mint: AccountInfo<'info>,
+    lamports: u64,
+) -> Result<()> {
+    if lamports == 0 {
+        return Ok(());
+    }
+
+    let temp_account = temp.0.clone();
+    create_temp_account(
+        system_program.clone(),
+        token_program.clone(),
+        signer.clone(),
+        temp,
+        native_mint,
+    )?;
+
+    let transfer_lamports_ctx = CpiContext::new(
+        system_program,
+        Transfer {
+            from: signer,
+            to: temp_account.clone(),
+        },
+    );
+
+    system_program::transfer(transfer_lamports_ctx, lamports)?;
+
+    let sync_native_ctx = CpiContext::new(
+        token_program,
+        SyncNative {
+            account: temp_account,
+        },
+    );
+
+    sync_native(sync_native_ctx)
+}
+
+// Moves `amount` wSOL out of `wsol_account` into the temporary account and
+// closes it, paying the unwrapped lamports and its rent back to the signer
+pub fn unwrap_sol<'info>(
+    system_program: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer: AccountInfo<'info>,
+    wsol_account: AccountInfo<'info>,
+    temp: (AccountInfo<'info>, u8),
+    native_mint: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let temp_account = temp.0.clone();
+    create_temp_account(
+        system_program,
+        token_program.clone(),
+        signer.clone(),
+        temp,
+        native_mint,
+    )?;
+
+    let transfer_to_temp_ctx = CpiContext::new(
+        token_program.clone(),
+        token::Transfer {
+            from: wsol_account,
+            to: temp_account.clone(),
+            authority: signer.clone(),
+        },
+    );
+
+    token::transfer(transfer_to_temp_ctx, amount)?;
+
+    close_temp_account(token_program, signer, temp_account)
+}
+
+// Closes the temporary account, paying whatever wSOL is left in it and its
+// rent back to the signer as lamports
+pub fn close_temp_account<'info>(
+    token_program: AccountInfo<'info>,
+    signer: AccountInfo<'info>,
+    temp_account: AccountInfo<'info>,
+) -> Result<()> {
+    let close_temp_ctx = CpiContext::new(
+        token_program,
+        CloseAccount {
+            account: temp_account,
+            destination: signer.clone(),
+            authority: signer,
+        },
+    );
+
+    close_account(close_temp_ctx)
+}
+
+fn create_temp_account<'info>(
+    system_program: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer: AccountInfo<'info>,
+    (temp_account, temp_bump): (AccountInfo<'info>, u8),
+    native_mint: AccountInfo<'info>,
+) -> Result<()> {
+    let signer_key = signer.key();
+    let seeds: &[&[u8]; 3] = &[b"wsol", signer_key.as_ref(), &[temp_bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    create_pda_account(
+        &signer,
+        &temp_account,
+        &system_program,
+        TokenAccount::LEN,
+        &token::ID,
+        signer_seeds,
+    )?;
+
+    let initialize_temp_ctx = CpiContext::new(
+        token_program,
+        InitializeAccount3 {
+            account: temp_account,
+            mint: native_mint,
+            authority: signer,
+        },
+    );
+
+    initialize_account3(initialize_temp_ctx)
+}
@@ -0,0 +1,143 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+use crate::{
+    instructions::allowlist::check_allowlist,
+    states::{AMMError, AllowlistEntry, DcaOrder, AMM},
+};
+
+// Sells `amount` of `input_mint` in tranches of amount_per_interval, one every
+// `interval` seconds, through `execute_dca`. The proceeds collect in the
+// output vault until the owner calls `close_dca`.
+
+#[derive(Accounts)]
+#[instruction(dca_id: u64)]
+pub struct OpenDca<'info> {
+    #[account(
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + DcaOrder::INIT_SPACE,
+        seeds = [
+            b"dca",
+            amm.key().as_ref(),
+            signer.key().as_ref(),
+            &dca_id.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub dca: Box<Account<'info, DcaOrder>>,
+
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"dca_input_vault", dca.key().as_ref()],
+        bump,
+        token::mint = input_mint,
+        token::authority = dca
+    )]
+    pub input_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"dca_output_vault", dca.key().as_ref()],
+        bump,
+        token::mint = output_mint,
+        token::authority = dca
+    )]
+    pub output_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = input_mint,
+        token::authority = signer
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+    #[account(
+        constraint = input_mint.key() == token_a_mint.key()
+            || input_mint.key() == token_b_mint.key() @ AMMError::InvalidOrder
+    )]
+    pub input_mint: Box<Account<'info, Mint>>,
+    #[account(
+        constraint = output_mint.key() == token_a_mint.key()
+            || output_mint.key() == token_b_mint.key() @ AMMError::InvalidOrder,
+        constraint = output_mint.key() != input_mint.key() @ AMMError::InvalidOrder
+    )]
+    pub output_mint: Box<Account<'info, Mint>>,
+
+    // permissioned pools need one of these
+    #[account(
+        seeds = [b"allowlist", amm.key().as_ref(), signer.key().as_ref()],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Option<Box<Account<'info, AllowlistEntry>>>,
+    pub allowlist_authority: Option<Signer<'info>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> OpenDca<'info> {
+    pub fn open_dca(
+        &mut self,
+        dca_id: u64,
+        amount: u64,
+        amount_per_interval: u64,
+        interval: i64,
+        min_out_per_interval: u64,
+        bumps: &OpenDcaBumps,
+    ) -> Result<()> {
+        check_allowlist(
+            &*self.amm.load()?,
+            self.allowlist_entry.as_deref(),
+            self.allowlist_authority.as_ref(),
+        )?;
+
+        require!(
+            amount > 0 && amount_per_interval > 0,
+            AMMError::InvalidQuantity
+        );
+        require!(interval > 0, AMMError::InvalidOrder);
+
+        // the first tranche can execute straight away
+        let now = Clock::get()?.unix_timestamp;
+        self.dca.set_inner(DcaOrder {
+            amm: self.amm.key(),
+            owner: self.signer.key(),
+            input_vault: self.input_vault.key(),
+            output_vault: self.output_vault.key(),
+            dca_id,
+            sell_a: self.input_mint.key() == self.token_a_mint.key(),
+            amount_per_interval,
+            interval,
+            min_out_per_interval,
+            next_execution_ts: now,
+            total_in: 0,
+            total_out: 0,
+            created_at: now,
+            bump: bumps.dca,
+        });
+
+        let deposit_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.user_token_account.to_account_info(),
+                to: self.input_vault.to_account_info(),
+                authority: self.signer.to_account_info(),
+            },
+        );
+
+        transfer(deposit_ctx, amount)
+    }
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    instructions::dynamic_fee::{pool_price, price_move_bps},
+    states::{AMMError, PriceFeed, AMM},
+};
+
+// ORACLE GUARD
+// Trades on a pool with an oracle must leave the pool price within
+// max_deviation_bps of the oracle price, and the oracle must have been
+// updated within max_oracle_age seconds. Both prices are compared as Q64.64
+// ratios of token B atoms per token A atom.
+
+// price X 10^expo whole B per whole A -> price X 10^(expo + dB - dA) B atoms per A atom
+pub fn oracle_pool_price(price: u64, expo: i32, decimals_a: u8, decimals_b: u8) -> Result<u128> {
+    let exponent = expo + decimals_b as i32 - decimals_a as i32;
+    let scale = 10u128
+        .checked_pow(exponent.unsigned_abs())
+        .ok_or(AMMError::ArithmeticOverflow)?;
+
+    let price = (price as u128) << 64;
+    let price = if exponent >= 0 {
+        price.checked_mul(scale)
+    } else {
+        price.checked_div(scale)
+    }
+    .ok_or(AMMError::ArithmeticOverflow)?;
+
+    require!(price > 0, AMMError::InvalidOracle);
+    Ok(price)
+}
+
+// Checks the post-trade reserves against the pool's oracle, if it has one
+pub fn check_oracle_price(
+    amm: &AMM,
+    oracle: Option<&Account<PriceFeed>>,
+    reserve_a: u64,
+    reserve_b: u64,
+    decimals_a: u8,
+    decimals_b: u8,
+    now: i64,
+) -> Result<()> {
+    if amm.oracle == Pubkey::default() {
+        return Ok(());
+    }
+
+    let oracle = oracle
+        .filter(|oracle| oracle.key() == amm.oracle)
+        .ok_or(AMMError::InvalidOracle)?;
+    require!(
+        now.saturating_sub(oracle.publish_time) <= amm.max_oracle_age,
+        AMMError::OracleStale
+    );
+
+    let oracle_price = oracle_pool_price(oracle.price, oracle.expo, decimals_a, decimals_b)?;
+    require!(
+        price_move_bps(oracle_price, pool_price(reserve_a, reserve_b))
+            <= amm.max_deviation_bps as u64,
+        AMMError::PriceDeviationExceeded
+    );
+
+    Ok(())
+}
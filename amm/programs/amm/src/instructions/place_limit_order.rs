@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+use crate::{
+    instructions::allowlist::check_allowlist,
+    states::{AMMError, AllowlistEntry, LimitOrder, AMM},
+};
+
+// The order sells all of amount_in of `input_mint` once the pool pays at
+// least min_amount_out for it. `fill_orders` executes it, `cancel_limit_order`
+// returns the escrowed tokens.
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct PlaceLimitOrder<'info> {
+    #[account(
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + LimitOrder::INIT_SPACE,
+        seeds = [
+            b"limit_order",
+            amm.key().as_ref(),
+            signer.key().as_ref(),
+            &order_id.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub order: Box<Account<'info, LimitOrder>>,
+
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"limit_order_vault", order.key().as_ref()],
+        bump,
+        token::mint = input_mint,
+        token::authority = order
+    )]
+    pub order_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = input_mint,
+        token::authority = signer
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+    #[account(
+        constraint = input_mint.key() == token_a_mint.key()
+            || input_mint.key() == token_b_mint.key() @ AMMError::InvalidOrder
+    )]
+    pub input_mint: Box<Account<'info, Mint>>,
+
+    // permissioned pools need one of these
+    #[account(
+        seeds = [b"allowlist", amm.key().as_ref(), signer.key().as_ref()],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Option<Box<Account<'info, AllowlistEntry>>>,
+    pub allowlist_authority: Option<Signer<'info>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> PlaceLimitOrder<'info> {
+    pub fn place_limit_order(
+        &mut self,
+        order_id: u64,
+        amount_in: u64,
+        min_amount_out: u64,
+        bumps: &PlaceLimitOrderBumps,
+    ) -> Result<()> {
+        check_allowlist(
+            &*self.amm.load()?,
+            self.allowlist_entry.as_deref(),
+            self.allowlist_authority.as_ref(),
+        )?;
+
+        require!(
+            amount_in > 0 && min_amount_out > 0,
+            AMMError::InvalidQuantity
+        );
+
+        self.order.set_inner(LimitOrder {
+            amm: self.amm.key(),
+            owner: self.signer.key(),
+            vault: self.order_vault.key(),
+            order_id,
+            sell_a: self.input_mint.key() == self.token_a_mint.key(),
+            amount_in,
+            min_amount_out,
+            created_at: Clock::get()?.unix_timestamp,
+            bump: bumps.order,
+        });
+
+        let escrow_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.user_token_account.to_account_info(),
+                to: self.order_vault.to_account_info(),
+                authority: self.signer.to_account_info(),
+            },
+        );
+
+        transfer(escrow_ctx, amount_in)
+    }
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    instructions::lp_fees::fee_growth_fees,
+    states::{AMMError, Position, AMM},
+};
+
+// Fees earned by a position's shares since its checkpoint
+pub fn pending_position_fees(amm: &AMM, position: &Position) -> Result<(u64, u64)> {
+    Ok((
+        fee_growth_fees(position.shares, amm.fee_growth_a, position.fee_growth_a)?,
+        fee_growth_fees(position.shares, amm.fee_growth_b, position.fee_growth_b)?,
+    ))
+}
+
+// Moves a position's pending fees into fees_accrued_a/b and its checkpoint up
+// to the pool's current growth. Must run before its shares change.
+pub fn settle_position_fees(amm: &AMM, position: &mut Position) -> Result<()> {
+    let (fees_a, fees_b) = pending_position_fees(amm, position)?;
+    position.fees_accrued_a = position
+        .fees_accrued_a
+        .checked_add(fees_a)
+        .ok_or(AMMError::ArithmeticOverflow)?;
+    position.fees_accrued_b = position
+        .fees_accrued_b
+        .checked_add(fees_b)
+        .ok_or(AMMError::ArithmeticOverflow)?;
+    position.fee_growth_a = amm.fee_growth_a;
+    position.fee_growth_b = amm.fee_growth_b;
+
+    Ok(())
+}
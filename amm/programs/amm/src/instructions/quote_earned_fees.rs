@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, TokenAccount};
+
+use crate::{
+    instructions::lp_fees::pending_lp_fees,
+    states::{AMMError, EarnedFees, LpFeeCheckpoint, AMM},
+};
+
+// Read-only - returns the swap fees the LP tokens tracked by a wallet's
+// checkpoint have earned as return data, so withdrawals can be split into
+// principal and fees. The LP account can be left out once it has been closed.
+
+#[derive(Accounts)]
+pub struct QuoteEarnedFees<'info> {
+    #[account(
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        seeds = [b"lp_fees", amm.key().as_ref(), owner.key().as_ref()],
+        bump = lp_fee_checkpoint.bump,
+        has_one = amm,
+        has_one = owner @ AMMError::Unauthorized,
+    )]
+    pub lp_fee_checkpoint: Box<Account<'info, LpFeeCheckpoint>>,
+
+    #[account(
+        associated_token::mint = lp_mint,
+        associated_token::authority = owner
+    )]
+    pub token_lp_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        seeds = [b"lp_mint", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: the LP whose fees are quoted
+    pub owner: UncheckedAccount<'info>,
+}
+
+impl<'info> QuoteEarnedFees<'info> {
+    pub fn quote_earned_fees(&self) -> Result<EarnedFees> {
+        let lp_balance = self
+            .token_lp_account
+            .as_ref()
+            .map_or(0, |account| account.amount);
+
+        let checkpoint = &self.lp_fee_checkpoint;
+        let (pending_a, pending_b) = pending_lp_fees(&*self.amm.load()?, checkpoint, lp_balance)?;
+
+        Ok(EarnedFees {
+            fees_a: checkpoint
+                .earned_a
+                .checked_add(pending_a)
+                .ok_or(AMMError::ArithmeticOverflow)?,
+            fees_b: checkpoint
+                .earned_b
+                .checked_add(pending_b)
+                .ok_or(AMMError::ArithmeticOverflow)?,
+        })
+    }
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::{
+    instructions::position::pending_position_fees,
+    states::{AMMError, EarnedFees, Position, AMM},
+};
+
+// Read-only - returns the swap fees a position has earned as return data,
+// including those since it was last touched. Unlike wallet LP balances, a
+// position's shares only change through its own instructions, so this is
+// exact.
+
+#[derive(Accounts)]
+pub struct QuotePositionFees<'info> {
+    #[account(
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        seeds = [b"position", position.position_mint.as_ref()],
+        bump = position.bump,
+        has_one = amm,
+    )]
+    pub position: Box<Account<'info, Position>>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+}
+
+impl<'info> QuotePositionFees<'info> {
+    pub fn quote_position_fees(&self) -> Result<EarnedFees> {
+        let position = &self.position;
+        let (pending_a, pending_b) = pending_position_fees(&*self.amm.load()?, position)?;
+
+        Ok(EarnedFees {
+            fees_a: position
+                .fees_accrued_a
+                .checked_add(pending_a)
+                .ok_or(AMMError::ArithmeticOverflow)?,
+            fees_b: position
+                .fees_accrued_b
+                .checked_add(pending_b)
+                .ok_or(AMMError::ArithmeticOverflow)?,
+        })
+    }
+}
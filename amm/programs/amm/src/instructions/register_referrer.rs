@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::states::{AMMError, Config, Referrer};
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ AMMError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Referrer::INIT_SPACE,
+        seeds = [b"referrer", owner.key().as_ref()],
+        bump,
+    )]
+    pub referrer: Box<Account<'info, Referrer>>,
+
+    /// CHECK: the wallet being registered
+    pub owner: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+impl<'info> RegisterReferrer<'info> {
+    pub fn register_referrer(&mut self, bumps: &RegisterReferrerBumps) -> Result<()> {
+        self.referrer.set_inner(Referrer {
+            owner: self.owner.key(),
+            registered_at: Clock::get()?.unix_timestamp,
+            bump: bumps.referrer,
+        });
+
+        Ok(())
+    }
+}
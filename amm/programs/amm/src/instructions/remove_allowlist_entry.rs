@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::states::{AMMError, AllowlistEntry, AllowlistEntryRemoved, AMM};
+
+#[derive(Accounts)]
+pub struct RemoveAllowlistEntry<'info> {
+    #[account(
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        has_one = allowlist_authority @ AMMError::Unauthorized,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        mut,
+        seeds = [b"allowlist", amm.key().as_ref(), allowlist_entry.wallet.as_ref()],
+        bump = allowlist_entry.bump,
+        close = allowlist_authority,
+    )]
+    pub allowlist_entry: Box<Account<'info, AllowlistEntry>>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub allowlist_authority: Signer<'info>,
+}
+
+impl<'info> RemoveAllowlistEntry<'info> {
+    pub fn remove_allowlist_entry(&mut self) -> Result<()> {
+        emit!(AllowlistEntryRemoved {
+            amm: self.amm.key(),
+            wallet: self.allowlist_entry.wallet,
+        });
+
+        Ok(())
+    }
+}
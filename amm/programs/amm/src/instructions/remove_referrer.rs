@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::states::{AMMError, Config, Referrer};
+
+#[derive(Accounts)]
+pub struct RemoveReferrer<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ AMMError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [b"referrer", referrer.owner.as_ref()],
+        bump = referrer.bump,
+        close = authority,
+    )]
+    pub referrer: Box<Account<'info, Referrer>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+impl<'info> RemoveReferrer<'info> {
+    pub fn remove_referrer(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
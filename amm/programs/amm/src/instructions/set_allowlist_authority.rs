@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::states::{AMMError, AMM};
+
+// `None` opens the pool to everyone again
+
+#[derive(Accounts)]
+pub struct SetAllowlistAuthority<'info> {
+    #[account(
+        mut,
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        has_one = creator @ AMMError::Unauthorized,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    pub creator: Signer<'info>,
+}
+
+impl<'info> SetAllowlistAuthority<'info> {
+    pub fn set_allowlist_authority(&mut self, authority: Option<Pubkey>) -> Result<()> {
+        let mut amm = self.amm.load_mut()?;
+        amm.allowlist_authority = authority.unwrap_or_default();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::states::{AMMError, AMM};
+
+// A window of 0 slots switches back to immediate execution. Intents already
+// submitted can still be settled (or cancelled) either way.
+// Every intent has to be at least min_amount_a (or min_amount_b) of its input
+// token, which is what stops a window being filled with dust intents.
+
+#[derive(Accounts)]
+pub struct SetBatchMode<'info> {
+    #[account(
+        mut,
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        has_one = creator @ AMMError::Unauthorized,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    pub creator: Signer<'info>,
+}
+
+impl<'info> SetBatchMode<'info> {
+    pub fn set_batch_mode(
+        &mut self,
+        window_slots: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    ) -> Result<()> {
+        let mut amm = self.amm.load_mut()?;
+        amm.batch_window_slots = window_slots;
+        amm.batch_min_amount_a = min_amount_a;
+        amm.batch_min_amount_b = min_amount_b;
+
+        Ok(())
+    }
+}
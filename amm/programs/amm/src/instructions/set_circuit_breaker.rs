@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::states::{AMMError, AMM, BPS_DENOMINATOR};
+
+// A window of 0 slots turns the breaker off
+
+#[derive(Accounts)]
+pub struct SetCircuitBreaker<'info> {
+    #[account(
+        mut,
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        has_one = creator @ AMMError::Unauthorized,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    pub creator: Signer<'info>,
+}
+
+impl<'info> SetCircuitBreaker<'info> {
+    pub fn set_circuit_breaker(
+        &mut self,
+        window_slots: u64,
+        max_price_change_bps: u16,
+        max_outflow_bps: u16,
+    ) -> Result<()> {
+        require!(
+            max_outflow_bps as u64 <= BPS_DENOMINATOR,
+            AMMError::InvalidCircuitBreaker
+        );
+        require!(
+            window_slots == 0 || max_price_change_bps > 0 || max_outflow_bps > 0,
+            AMMError::InvalidCircuitBreaker
+        );
+
+        let mut amm = self.amm.load_mut()?;
+        amm.breaker_window_slots = window_slots;
+        amm.max_price_change_bps = max_price_change_bps;
+        amm.max_outflow_bps = max_outflow_bps;
+
+        // the next trade opens a fresh window under the new limits
+        amm.window_start_slot = 0;
+        amm.window_start_price = 0;
+        amm.window_start_reserve_a = 0;
+        amm.window_start_reserve_b = 0;
+        amm.window_outflow_a = 0;
+        amm.window_outflow_b = 0;
+
+        Ok(())
+    }
+}
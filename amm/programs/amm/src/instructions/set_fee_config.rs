@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::states::{AMMError, AMM, MAX_FEE_BPS};
+
+#[derive(Accounts)]
+pub struct SetFeeConfig<'info> {
+    #[account(
+        mut,
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        has_one = creator @ AMMError::Unauthorized,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    pub creator: Signer<'info>,
+}
+
+impl<'info> SetFeeConfig<'info> {
+    pub fn set_fee_config(
+        &mut self,
+        min_fee_bps: u16,
+        max_fee_bps: u16,
+        dynamic_fee: bool,
+    ) -> Result<()> {
+        require!(
+            min_fee_bps <= max_fee_bps && max_fee_bps <= MAX_FEE_BPS,
+            AMMError::InvalidFee
+        );
+
+        let mut amm = self.amm.load_mut()?;
+        amm.min_fee_bps = min_fee_bps;
+        amm.max_fee_bps = max_fee_bps;
+        amm.dynamic_fee = dynamic_fee as u8;
+
+        // start the accumulator over so a mode switch doesn't act on stale state
+        amm.volatility = 0;
+        amm.last_price = 0;
+        amm.last_swap_ts = 0;
+
+        Ok(())
+    }
+}
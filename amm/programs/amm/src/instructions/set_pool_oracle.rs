@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::states::{AMMError, PriceFeed, AMM, BPS_DENOMINATOR};
+
+// Passing no oracle removes the guard
+
+#[derive(Accounts)]
+pub struct SetPoolOracle<'info> {
+    #[account(
+        mut,
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        has_one = creator @ AMMError::Unauthorized,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    pub oracle: Option<Account<'info, PriceFeed>>,
+
+    pub creator: Signer<'info>,
+}
+
+impl<'info> SetPoolOracle<'info> {
+    pub fn set_pool_oracle(&mut self, max_deviation_bps: u16, max_oracle_age: i64) -> Result<()> {
+        let mut amm = self.amm.load_mut()?;
+
+        match &self.oracle {
+            Some(oracle) => {
+                require!(
+                    max_deviation_bps > 0 && max_deviation_bps as u64 <= BPS_DENOMINATOR,
+                    AMMError::InvalidOracle
+                );
+                require!(max_oracle_age > 0, AMMError::InvalidOracle);
+
+                amm.oracle = oracle.key();
+                amm.max_deviation_bps = max_deviation_bps;
+                amm.max_oracle_age = max_oracle_age;
+            }
+            None => {
+                amm.oracle = Pubkey::default();
+                amm.max_deviation_bps = 0;
+                amm.max_oracle_age = 0;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::states::{AMMError, Config, AMM};
+
+#[derive(Accounts)]
+pub struct SetReferralFee<'info> {
+    #[account(
+        mut,
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        has_one = creator @ AMMError::Unauthorized,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    pub creator: Signer<'info>,
+}
+
+impl<'info> SetReferralFee<'info> {
+    pub fn set_referral_fee(&mut self, referral_fee_bps: u16) -> Result<()> {
+        require!(
+            referral_fee_bps <= self.config.max_referral_fee_bps,
+            AMMError::ReferralFeeTooHigh
+        );
+
+        let mut amm = self.amm.load_mut()?;
+        amm.referral_fee_bps = referral_fee_bps;
+
+        Ok(())
+    }
+}
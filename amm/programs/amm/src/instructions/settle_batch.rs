@@ -0,0 +1,302 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+use crate::{
+    instructions::{
+        batch::{batch_clearing, check_intents, close_batch, start_pass},
+        circuit_breaker::check_circuit_breaker,
+        dynamic_fee::{amount_after_fee, update_swap_fee},
+        lp_fees::accrue_lp_fee,
+        oracle::check_oracle_price,
+    },
+    states::{AMMError, Batch, BatchSettled, PriceFeed, SwapIntent, AMM},
+};
+
+// remaining_accounts: a page of the batch's intents
+
+// Permissionless crank, once the batch window has ended. All intents clear at
+// the price from `batch_clearing`, so their order within the window doesn't
+// matter. Intents whose min_amount_out isn't met at that price are excluded
+// and the price is recomputed without them, until every remaining intent is
+// satisfied. Only the net imbalance trades against the pool - the outputs are
+// then claimed from the batch vaults with `claim_intent`.
+// Large batches are settled over several calls, each with any page of the
+// intents: every pass has to see all intents still in before the next pass
+// starts or the trade executes. If the reserves moved against the batch
+// since the pass was priced (deposits and withdrawals still run in batch
+// mode), the pass is repeated at the new reserves.
+
+#[derive(Accounts)]
+pub struct SettleBatch<'info> {
+    #[account(
+        mut,
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve_a", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = pool_authority
+    )]
+    pub reserve_a: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"reserve_b", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = pool_authority
+    )]
+    pub reserve_b: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: pool authority over token reserves and lp mint
+    #[account(
+        seeds=[b"authority", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"batch", amm.key().as_ref(), &batch.start_slot.to_le_bytes()],
+        bump = batch.bump,
+    )]
+    pub batch: Box<Account<'info, Batch>>,
+
+    #[account(
+        mut,
+        address = batch.vault_a,
+    )]
+    pub batch_vault_a: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        address = batch.vault_b,
+    )]
+    pub batch_vault_b: Box<Account<'info, TokenAccount>>,
+
+    // required when the pool has an oracle configured
+    pub oracle: Option<Account<'info, PriceFeed>>,
+
+    /// CHECK: gets the rent back if the batch turns out to be empty
+    #[account(mut, address = batch.payer)]
+    pub payer: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub signer: Signer<'info>,
+}
+
+impl<'info> SettleBatch<'info> {
+    pub fn settle_batch(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+        bumps: &SettleBatchBumps,
+    ) -> Result<()> {
+        require!(!self.batch.settled, AMMError::BatchSettled);
+        require!(
+            Clock::get()?.slot >= self.batch.end_slot,
+            AMMError::BatchNotReady
+        );
+
+        // every intent was cancelled, so there is nothing left to claim
+        if self.batch.intent_count == 0 {
+            return close_batch(
+                &mut self.batch,
+                [&mut self.batch_vault_a, &mut self.batch_vault_b],
+                [
+                    self.reserve_a.to_account_info(),
+                    self.reserve_b.to_account_info(),
+                ],
+                &self.payer.to_account_info(),
+                &self.token_program.to_account_info(),
+            );
+        }
+        require!(!remaining_accounts.is_empty(), AMMError::InvalidIntent);
+
+        let batch_key = self.batch.key();
+        let mut intents = Vec::with_capacity(remaining_accounts.len());
+        for (i, info) in remaining_accounts.iter().enumerate() {
+            require_keys_eq!(*info.owner, crate::ID, AMMError::InvalidIntent);
+            require!(
+                remaining_accounts[..i]
+                    .iter()
+                    .all(|other| other.key != info.key),
+                AMMError::InvalidIntent
+            );
+
+            let intent = SwapIntent::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            require_keys_eq!(intent.batch, batch_key, AMMError::InvalidIntent);
+            intents.push(intent);
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let mut amm = self.amm.load_mut()?;
+        require!(amm.lp_supply > 0, AMMError::EmptyPool);
+
+        let reserve_a = self.reserve_a.amount;
+        let reserve_b = self.reserve_b.amount;
+
+        // the first page fixes the fee for the whole batch
+        let batch = &mut self.batch;
+        if batch.pass == 0 {
+            batch.fee_bps = update_swap_fee(&mut amm, reserve_a, reserve_b, now);
+            start_pass(batch, reserve_a, reserve_b)?;
+        }
+        let fee_bps = batch.fee_bps;
+
+        // a new pass either has fewer intents than the last, or follows a
+        // price drop and is priced at these same reserves - so this ends
+        let cleared = loop {
+            check_intents(batch, &mut intents)?;
+            if batch.checked_count < batch.pass_intents {
+                break None;
+            }
+
+            if batch.excluded_in_pass == 0 {
+                let (out_a, out_b) = batch_clearing(
+                    batch.total_in_a,
+                    batch.total_in_b,
+                    reserve_a,
+                    reserve_b,
+                    fee_bps,
+                )?;
+                if out_a >= batch.total_out_a && out_b >= batch.total_out_b {
+                    break Some((batch.total_in_a, batch.total_in_b, out_a, out_b));
+                }
+            }
+
+            start_pass(batch, reserve_a, reserve_b)?;
+        };
+
+        for (intent, info) in intents.iter().zip(remaining_accounts) {
+            intent.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        }
+
+        // the rest of the intents come in later pages
+        let Some((in_a, in_b, out_a, out_b)) = cleared else {
+            return Ok(());
+        };
+
+        // the net trade: A into the pool and B out of it, or the other way round
+        let net_a_in = out_a < in_a;
+        let (net_in, net_out) = if net_a_in {
+            (in_a - out_a, out_b - in_b)
+        } else {
+            (in_b - out_b, out_a - in_a)
+        };
+        let (reserve_a_after, reserve_b_after, outflow) = if net_a_in {
+            (reserve_a + net_in, reserve_b - net_out, (0, net_out))
+        } else {
+            (reserve_a - net_out, reserve_b + net_in, (net_out, 0))
+        };
+        let (batch_vault_in, reserve_in, reserve_out, batch_vault_out) = if net_a_in {
+            (
+                &self.batch_vault_a,
+                &self.reserve_a,
+                &self.reserve_b,
+                &self.batch_vault_b,
+            )
+        } else {
+            (
+                &self.batch_vault_b,
+                &self.reserve_b,
+                &self.reserve_a,
+                &self.batch_vault_a,
+            )
+        };
+        require!(
+            net_out <= reserve_out.amount,
+            AMMError::InsufficientReserves
+        );
+
+        check_oracle_price(
+            &amm,
+            self.oracle.as_ref(),
+            reserve_a_after,
+            reserve_b_after,
+            self.token_a_mint.decimals,
+            self.token_b_mint.decimals,
+            now,
+        )?;
+        check_circuit_breaker(
+            &mut amm,
+            reserve_a,
+            reserve_b,
+            reserve_a_after,
+            reserve_b_after,
+            outflow,
+            Clock::get()?.slot,
+        )?;
+
+        // only the net trade pays the swap fee
+        let fee = net_in - amount_after_fee(net_in, fee_bps)?;
+        accrue_lp_fee(&mut amm, fee, net_a_in);
+        drop(amm);
+
+        let amm_key = self.amm.key();
+        let start_slot = self.batch.start_slot.to_le_bytes();
+        let seeds: &[&[u8]; 4] = &[b"batch", amm_key.as_ref(), &start_slot, &[self.batch.bump]];
+        let batch_signer_seeds = &[&seeds[..]];
+
+        if net_in > 0 {
+            let transfer_to_reserve_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: batch_vault_in.to_account_info(),
+                    to: reserve_in.to_account_info(),
+                    authority: self.batch.to_account_info(),
+                },
+                batch_signer_seeds,
+            );
+
+            transfer(transfer_to_reserve_ctx, net_in)?;
+        }
+
+        if net_out > 0 {
+            let token_a_mint_key = self.token_a_mint.key();
+            let token_b_mint_key = self.token_b_mint.key();
+
+            let seeds: &[&[u8]; 4] = &[
+                b"authority",
+                token_a_mint_key.as_ref(),
+                token_b_mint_key.as_ref(),
+                &[bumps.pool_authority],
+            ];
+            let pool_signer_seeds = &[&seeds[..]];
+
+            let transfer_to_batch_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: reserve_out.to_account_info(),
+                    to: batch_vault_out.to_account_info(),
+                    authority: self.pool_authority.to_account_info(),
+                },
+                pool_signer_seeds,
+            );
+
+            transfer(transfer_to_batch_ctx, net_out)?;
+        }
+
+        let batch = &mut self.batch;
+        batch.total_out_a = out_a;
+        batch.total_out_b = out_b;
+        batch.settled = true;
+
+        emit!(BatchSettled {
+            amm: amm_key,
+            batch: batch_key,
+            total_in_a: in_a,
+            total_in_b: in_b,
+            total_out_a: out_a,
+            total_out_b: out_b,
+            excluded_intents: batch.excluded_count,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+use crate::{
+    instructions::farm::{checkpoint_farm_position, settle_farm_position, update_farm_rewards},
+    states::{AMMError, Farm, FarmPosition},
+};
+
+#[derive(Accounts)]
+pub struct StakeLp<'info> {
+    #[account(
+        mut,
+        seeds = [b"farm", farm.amm.as_ref()],
+        bump = farm.bump,
+        has_one = lp_mint,
+        has_one = lp_vault,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + FarmPosition::INIT_SPACE,
+        seeds = [b"farm_position", farm.key().as_ref(), signer.key().as_ref()],
+        bump,
+    )]
+    pub position: Box<Account<'info, FarmPosition>>,
+
+    #[account(mut)]
+    pub lp_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = signer
+    )]
+    pub token_lp_account: Box<Account<'info, TokenAccount>>,
+
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> StakeLp<'info> {
+    pub fn stake_lp(&mut self, amount: u64, bumps: &StakeLpBumps) -> Result<()> {
+        require!(amount > 0, AMMError::InvalidQuantity);
+
+        if self.position.owner == Pubkey::default() {
+            self.position.farm = self.farm.key();
+            self.position.owner = self.signer.key();
+            self.position.bump = bumps.position;
+        }
+
+        update_farm_rewards(&mut self.farm, Clock::get()?.unix_timestamp)?;
+        settle_farm_position(&self.farm, &mut self.position)?;
+
+        let stake_lp_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.token_lp_account.to_account_info(),
+                to: self.lp_vault.to_account_info(),
+                authority: self.signer.to_account_info(),
+            },
+        );
+
+        transfer(stake_lp_ctx, amount)?;
+
+        self.position.amount = self
+            .position
+            .amount
+            .checked_add(amount)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+        self.farm.total_staked = self
+            .farm
+            .total_staked
+            .checked_add(amount)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+
+        checkpoint_farm_position(&self.farm, &mut self.position)
+    }
+}
@@ -0,0 +1,193 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+use crate::{
+    instructions::allowlist::check_allowlist,
+    states::{AMMError, AllowlistEntry, Batch, SwapIntent, AMM, BATCH_MAX_INTENTS},
+};
+
+// Batches are keyed by the first slot of their window,
+// start_slot = slot - slot % batch_window_slots, and created by the first
+// intent submitted in it.
+
+#[derive(Accounts)]
+#[instruction(start_slot: u64)]
+pub struct SubmitIntent<'info> {
+    #[account(
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + Batch::INIT_SPACE,
+        seeds = [b"batch", amm.key().as_ref(), &start_slot.to_le_bytes()],
+        bump,
+    )]
+    pub batch: Box<Account<'info, Batch>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"batch_vault_a", batch.key().as_ref()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = batch
+    )]
+    pub batch_vault_a: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"batch_vault_b", batch.key().as_ref()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = batch
+    )]
+    pub batch_vault_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + SwapIntent::INIT_SPACE,
+        seeds = [b"swap_intent", batch.key().as_ref(), signer.key().as_ref()],
+        bump,
+    )]
+    pub intent: Box<Account<'info, SwapIntent>>,
+
+    #[account(
+        mut,
+        token::authority = signer
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    // permissioned pools need one of these
+    #[account(
+        seeds = [b"allowlist", amm.key().as_ref(), signer.key().as_ref()],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Option<Box<Account<'info, AllowlistEntry>>>,
+    pub allowlist_authority: Option<Signer<'info>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> SubmitIntent<'info> {
+    pub fn submit_intent(
+        &mut self,
+        start_slot: u64,
+        amount_in: u64,
+        sell_a: bool,
+        min_amount_out: u64,
+        bumps: &SubmitIntentBumps,
+    ) -> Result<()> {
+        let amm = self.amm.load()?;
+        check_allowlist(
+            &amm,
+            self.allowlist_entry.as_deref(),
+            self.allowlist_authority.as_ref(),
+        )?;
+
+        require!(amount_in > 0, AMMError::InvalidQuantity);
+
+        let window_slots = amm.batch_window_slots;
+        require!(window_slots > 0, AMMError::BatchModeInactive);
+        let min_amount = if sell_a {
+            amm.batch_min_amount_a
+        } else {
+            amm.batch_min_amount_b
+        };
+        require!(amount_in >= min_amount, AMMError::IntentTooSmall);
+        drop(amm);
+
+        let slot = Clock::get()?.slot;
+        require!(
+            start_slot == slot - slot % window_slots,
+            AMMError::BatchClosed
+        );
+
+        let batch = &mut self.batch;
+        if batch.amm == Pubkey::default() {
+            batch.set_inner(Batch {
+                amm: self.amm.key(),
+                payer: self.signer.key(),
+                start_slot,
+                end_slot: start_slot + window_slots,
+                vault_a: self.batch_vault_a.key(),
+                vault_b: self.batch_vault_b.key(),
+                total_in_a: 0,
+                total_in_b: 0,
+                total_out_a: 0,
+                total_out_b: 0,
+                paid_out_a: 0,
+                paid_out_b: 0,
+                intent_count: 0,
+                sell_a_count: 0,
+                sell_b_count: 0,
+                excluded_count: 0,
+                fee_bps: 0,
+                pass: 0,
+                pass_in_a: 0,
+                pass_in_b: 0,
+                pass_intents: 0,
+                checked_count: 0,
+                excluded_in_pass: 0,
+                settled: false,
+                bump: bumps.batch,
+            });
+        }
+        // also turns away a batch left over from before the window length changed
+        require!(
+            !batch.settled && slot < batch.end_slot,
+            AMMError::BatchClosed
+        );
+        require!(batch.intent_count < BATCH_MAX_INTENTS, AMMError::BatchFull);
+
+        let (vault, mint) = if sell_a {
+            batch.total_in_a = batch
+                .total_in_a
+                .checked_add(amount_in)
+                .ok_or(AMMError::ArithmeticOverflow)?;
+            batch.sell_a_count += 1;
+            (&self.batch_vault_a, self.token_a_mint.key())
+        } else {
+            batch.total_in_b = batch
+                .total_in_b
+                .checked_add(amount_in)
+                .ok_or(AMMError::ArithmeticOverflow)?;
+            batch.sell_b_count += 1;
+            (&self.batch_vault_b, self.token_b_mint.key())
+        };
+        require_keys_eq!(self.user_token_account.mint, mint, AMMError::InvalidIntent);
+        batch.intent_count += 1;
+
+        self.intent.set_inner(SwapIntent {
+            batch: batch.key(),
+            owner: self.signer.key(),
+            sell_a,
+            amount_in,
+            min_amount_out,
+            excluded: false,
+            checked_pass: 0,
+            bump: bumps.intent,
+        });
+
+        let escrow_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.user_token_account.to_account_info(),
+                to: vault.to_account_info(),
+                authority: self.signer.to_account_info(),
+            },
+        );
+
+        transfer(escrow_ctx, amount_in)
+    }
+}
@@ -1,7 +1,24 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{transfer, Mint, Token, TokenAccount, Transfer},
+};
 
-use crate::states::{AMMError, AMM};
+use crate::{
+    instructions::{
+        allowlist::check_allowlist,
+        circuit_breaker::check_circuit_breaker,
+        dynamic_fee::{amount_after_fee, update_swap_fee},
+        lp_fees::accrue_lp_fee,
+        native_sol::{close_temp_account, input_account, is_native_a, unwrap_sol, wrap_sol},
+        oracle::check_oracle_price,
+        utils::swap_output,
+    },
+    states::{
+        AMMError, AllowlistEntry, Config, PriceFeed, Referrer, SwapReferral, AMM, BPS_DENOMINATOR,
+        LAUNCH_ACTIVE,
+    },
+};
 
 #[derive(Accounts)]
 pub struct Swap<'info> {
@@ -13,14 +30,16 @@ pub struct Swap<'info> {
     pub amm: AccountLoader<'info, AMM>,
 
     #[account(
-        mut,
+        init_if_needed,
+        payer = signer,
         associated_token::mint = token_a_mint,
         associated_token::authority = signer
     )]
     pub token_a_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
-        mut,
+        init_if_needed,
+        payer = signer,
         associated_token::mint = token_b_mint,
         associated_token::authority = signer
     )]
@@ -53,7 +72,35 @@ pub struct Swap<'info> {
     pub token_a_mint: Box<Account<'info, Mint>>,
     pub token_b_mint: Box<Account<'info, Mint>>,
 
+    // required when the pool has an oracle configured
+    pub oracle: Option<Account<'info, PriceFeed>>,
+
+    // optional referrer, paid in the input token - the token account's owner
+    // must be registered, and the config is needed for the cap
+    #[account(mut)]
+    pub referrer_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    #[account(seeds = [b"referrer", referrer.owner.as_ref()], bump = referrer.bump)]
+    pub referrer: Option<Box<Account<'info, Referrer>>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Option<Box<Account<'info, Config>>>,
+
+    // permissioned pools need one of these
+    #[account(
+        seeds = [b"allowlist", amm.key().as_ref(), signer.key().as_ref()],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Option<Box<Account<'info, AllowlistEntry>>>,
+    pub allowlist_authority: Option<Signer<'info>>,
+
+    // temporary account the `_sol` variant wraps its input into or unwraps its
+    // output through
+    #[account(mut, seeds = [b"wsol", signer.key().as_ref()], bump)]
+    /// CHECK: created and closed by wrap_sol / unwrap_sol within the instruction
+    pub temp_wsol_account: Option<UncheckedAccount<'info>>,
+
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -61,9 +108,15 @@ pub struct Swap<'info> {
 
 impl<'info> Swap<'info> {
     pub fn swap(&mut self, quantity: u64, is_a: bool, bumps: &SwapBumps) -> Result<()> {
+        check_allowlist(
+            &*self.amm.load()?,
+            self.allowlist_entry.as_deref(),
+            self.allowlist_authority.as_ref(),
+        )?;
+
         require!(quantity > 0, AMMError::InvalidQuantity);
 
-        let (from_reserve, to_reserve, from_token_account, to_token_account) = if is_a {
+        let (reserve_in, reserve_out, user_in_account, user_out_account) = if is_a {
             (
                 &self.reserve_a,
                 &self.reserve_b,
@@ -91,27 +144,124 @@ impl<'info> Swap<'info> {
         // here,
         // dx = quantity
         // dy = other token quantity
-        // x = to_reserve_amount
-        // y = from_reserve_amount
+        // x = reserve_in amount
+        // y = reserve_out amount
 
         // amountOut = (reserveOut * amountIn) / (reserveIn + amountIn)
 
-        let other_token_quantity = from_reserve
+        // in batch mode trades only execute through settle_batch
+        let mut amm = self.amm.load_mut()?;
+        require!(amm.batch_window_slots == 0, AMMError::BatchModeActive);
+
+        // a launch pool trades through swap_launch until it graduates
+        require!(amm.launch_state != LAUNCH_ACTIVE, AMMError::LaunchActive);
+
+        // the fee is taken off the input before pricing, the rest of the
+        // input goes into the reserve
+        let now = Clock::get()?.unix_timestamp;
+        let fee_bps = update_swap_fee(&mut amm, self.reserve_a.amount, self.reserve_b.amount, now);
+
+        let amount_in = amount_after_fee(quantity, fee_bps)?;
+        let other_token_quantity = swap_output(amount_in, reserve_in.amount, reserve_out.amount)?;
+
+        // a referrer gets referral_fee_bps of the fee (capped by the config),
+        // the remainder stays in the reserve for LPs
+        // referral = (quantity - amountIn) X referralFee / 10000
+        let swap_fee = quantity - amount_in;
+        let referral_amount = match &self.referrer_token_account {
+            Some(referrer_token_account) => {
+                let config = self.config.as_ref().ok_or(AMMError::InvalidReferrer)?;
+                let referrer = self.referrer.as_ref().ok_or(AMMError::InvalidReferrer)?;
+                require_keys_eq!(
+                    referrer_token_account.mint,
+                    user_in_account.mint,
+                    AMMError::InvalidReferrer
+                );
+                require_keys_eq!(
+                    referrer_token_account.owner,
+                    referrer.owner,
+                    AMMError::InvalidReferrer
+                );
+                require_keys_neq!(referrer.owner, self.signer.key(), AMMError::SelfReferral);
+
+                let referral_fee_bps = amm.referral_fee_bps.min(config.max_referral_fee_bps);
+                (swap_fee as u128 * referral_fee_bps as u128 / BPS_DENOMINATOR as u128) as u64
+            }
+            None => 0,
+        };
+
+        let reserve_in_after = reserve_in
             .amount
-            .checked_mul(quantity)
-            .and_then(|p| p.checked_div(to_reserve.amount.checked_add(quantity)?))
+            .checked_add(quantity - referral_amount)
             .ok_or(AMMError::ArithmeticOverflow)?;
+        let reserve_out_after = reserve_out.amount - other_token_quantity;
+        let (reserve_a_after, reserve_b_after) = if is_a {
+            (reserve_in_after, reserve_out_after)
+        } else {
+            (reserve_out_after, reserve_in_after)
+        };
+
+        check_oracle_price(
+            &amm,
+            self.oracle.as_ref(),
+            reserve_a_after,
+            reserve_b_after,
+            self.token_a_mint.decimals,
+            self.token_b_mint.decimals,
+            now,
+        )?;
+
+        let outflow = if is_a {
+            (0, other_token_quantity)
+        } else {
+            (other_token_quantity, 0)
+        };
+        check_circuit_breaker(
+            &mut amm,
+            self.reserve_a.amount,
+            self.reserve_b.amount,
+            reserve_a_after,
+            reserve_b_after,
+            outflow,
+            Clock::get()?.slot,
+        )?;
+        accrue_lp_fee(&mut amm, swap_fee - referral_amount, is_a);
+        drop(amm);
 
+        let payer_in_account = input_account(user_in_account, self.temp_wsol_account.as_ref());
         let transfer_to_reserve = CpiContext::new(
             self.token_program.to_account_info(),
             Transfer {
-                from: from_token_account.to_account_info(),
-                to: to_reserve.to_account_info(),
+                from: payer_in_account.clone(),
+                to: reserve_in.to_account_info(),
                 authority: self.signer.to_account_info(),
             },
         );
 
-        transfer(transfer_to_reserve, quantity)?;
+        transfer(transfer_to_reserve, quantity - referral_amount)?;
+
+        if let Some(referrer_token_account) = &self.referrer_token_account {
+            if referral_amount > 0 {
+                let pay_referrer_ctx = CpiContext::new(
+                    self.token_program.to_account_info(),
+                    Transfer {
+                        from: payer_in_account,
+                        to: referrer_token_account.to_account_info(),
+                        authority: self.signer.to_account_info(),
+                    },
+                );
+
+                transfer(pay_referrer_ctx, referral_amount)?;
+            }
+
+            emit!(SwapReferral {
+                amm: self.amm.key(),
+                referrer_token_account: referrer_token_account.key(),
+                mint: user_in_account.mint,
+                swap_fee,
+                referral_amount,
+            });
+        }
 
         let token_a_mint_key = self.token_a_mint.key();
         let token_b_mint_key = self.token_b_mint.key();
@@ -128,8 +278,8 @@ impl<'info> Swap<'info> {
         let transfer_to_user = CpiContext::new_with_signer(
             self.token_program.to_account_info(),
             Transfer {
-                from: from_reserve.to_account_info(),
-                to: to_token_account.to_account_info(),
+                from: reserve_out.to_account_info(),
+                to: user_out_account.to_account_info(),
                 authority: self.pool_authority.to_account_info(),
             },
             signer_seeds,
@@ -139,4 +289,81 @@ impl<'info> Swap<'info> {
 
         Ok(())
     }
+
+    pub fn wrap_native(&mut self, quantity: u64, is_a: bool, bumps: &SwapBumps) -> Result<()> {
+        let native_a = is_native_a(&self.token_a_mint.key(), &self.token_b_mint.key())?;
+
+        // only the input side is wrapped
+        if is_a != native_a {
+            return Ok(());
+        }
+
+        let temp_wsol_account = self
+            .temp_wsol_account
+            .as_ref()
+            .ok_or(AMMError::MissingTempWsolAccount)?
+            .to_account_info();
+        let native_mint = self.native_accounts()?.1;
+
+        wrap_sol(
+            self.system_program.to_account_info(),
+            self.token_program.to_account_info(),
+            self.signer.to_account_info(),
+            (temp_wsol_account, bumps.temp_wsol_account),
+            native_mint,
+            quantity,
+        )
+    }
+
+    fn native_accounts(
+        &mut self,
+    ) -> Result<(&mut Box<Account<'info, TokenAccount>>, AccountInfo<'info>)> {
+        if is_native_a(&self.token_a_mint.key(), &self.token_b_mint.key())? {
+            Ok((
+                &mut self.token_a_account,
+                self.token_a_mint.to_account_info(),
+            ))
+        } else {
+            Ok((
+                &mut self.token_b_account,
+                self.token_b_mint.to_account_info(),
+            ))
+        }
+    }
+
+    // wSOL the signer holds before the instruction runs
+    pub fn native_balance(&mut self) -> Result<u64> {
+        Ok(self.native_accounts()?.0.amount)
+    }
+
+    // Closes the temporary account a wSOL input was paid from, or unwraps only
+    // what the instruction added on top of `balance_before` for a wSOL output
+    pub fn unwrap_native(&mut self, balance_before: u64, bumps: &SwapBumps) -> Result<()> {
+        let system_program = self.system_program.to_account_info();
+        let token_program = self.token_program.to_account_info();
+        let signer = self.signer.to_account_info();
+        let temp_wsol_account = self
+            .temp_wsol_account
+            .as_ref()
+            .ok_or(AMMError::MissingTempWsolAccount)?
+            .to_account_info();
+
+        if !temp_wsol_account.data_is_empty() {
+            return close_temp_account(token_program, signer, temp_wsol_account);
+        }
+
+        let (wsol_account, native_mint) = self.native_accounts()?;
+        wsol_account.reload()?;
+        let added = wsol_account.amount.saturating_sub(balance_before);
+
+        unwrap_sol(
+            system_program,
+            token_program,
+            signer,
+            wsol_account.to_account_info(),
+            (temp_wsol_account, bumps.temp_wsol_account),
+            native_mint,
+            added,
+        )
+    }
 }
@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{transfer, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    instructions::{dynamic_fee::amount_after_fee, utils::swap_output},
+    states::{AMMError, AMM, LAUNCH_ACTIVE},
+};
+
+// Buys or sells the launched token against a launch pool. Priced with the
+// same xy=k math as `swap`, but x and y include the launch pool's virtual
+// reserves, and only the real reserves can pay out. The fee stays in the
+// launch reserves and moves into the standard pool on graduation.
+
+#[derive(Accounts)]
+pub struct SwapLaunch<'info> {
+    #[account(
+        mut,
+        seeds=[b"launch", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub launch: AccountLoader<'info, AMM>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = token_a_mint,
+        associated_token::authority = signer
+    )]
+    pub token_a_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = token_b_mint,
+        associated_token::authority = signer
+    )]
+    pub token_b_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"launch_reserve_a", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = pool_authority
+    )]
+    pub launch_reserve_a: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"launch_reserve_b", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = pool_authority
+    )]
+    pub launch_reserve_b: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: pool authority over token reserves and lp mint
+    #[account(
+        seeds=[b"authority", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> SwapLaunch<'info> {
+    pub fn swap_launch(
+        &mut self,
+        quantity: u64,
+        is_a: bool,
+        bumps: &SwapLaunchBumps,
+    ) -> Result<()> {
+        require!(quantity > 0, AMMError::InvalidQuantity);
+
+        let (reserve_in, reserve_out, user_in_account, user_out_account) = if is_a {
+            (
+                &self.launch_reserve_a,
+                &self.launch_reserve_b,
+                &self.token_a_account,
+                &self.token_b_account,
+            )
+        } else {
+            (
+                &self.launch_reserve_b,
+                &self.launch_reserve_a,
+                &self.token_b_account,
+                &self.token_a_account,
+            )
+        };
+
+        let launch = self.launch.load()?;
+        require!(
+            launch.launch_state == LAUNCH_ACTIVE,
+            AMMError::InvalidLaunch
+        );
+
+        // x and y are real + virtual reserves - the virtual reserve is zero
+        // on the base side
+        let (virtual_in, virtual_out) = if is_a {
+            (launch.virtual_reserve_a, launch.virtual_reserve_b)
+        } else {
+            (launch.virtual_reserve_b, launch.virtual_reserve_a)
+        };
+        let effective_in = reserve_in
+            .amount
+            .checked_add(virtual_in)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+        let effective_out = reserve_out
+            .amount
+            .checked_add(virtual_out)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+
+        let amount_in = amount_after_fee(quantity, launch.min_fee_bps)?;
+        let other_token_quantity = swap_output(amount_in, effective_in, effective_out)?;
+        require!(
+            other_token_quantity <= reserve_out.amount,
+            AMMError::InsufficientReserves
+        );
+        drop(launch);
+
+        let transfer_to_reserve = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: user_in_account.to_account_info(),
+                to: reserve_in.to_account_info(),
+                authority: self.signer.to_account_info(),
+            },
+        );
+
+        transfer(transfer_to_reserve, quantity)?;
+
+        let token_a_mint_key = self.token_a_mint.key();
+        let token_b_mint_key = self.token_b_mint.key();
+
+        let seeds: &[&[u8]; 4] = &[
+            b"authority",
+            token_a_mint_key.as_ref(),
+            token_b_mint_key.as_ref(),
+            &[bumps.pool_authority],
+        ];
+
+        let signer_seeds = &[&seeds[..]];
+
+        let transfer_to_user = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: reserve_out.to_account_info(),
+                to: user_out_account.to_account_info(),
+                authority: self.pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(transfer_to_user, other_token_quantity)
+    }
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+
+use crate::{
+    instructions::{
+        dynamic_fee::amount_after_fee,
+        multi_pool::{reserve_amount, transfer_from_reserve, transfer_to_reserve},
+        utils::swap_output,
+    },
+    states::{AMMError, MultiPool},
+};
+
+// remaining_accounts: [reserve_in, reserve_out, user_in_account, user_out_account]
+
+#[derive(Accounts)]
+pub struct SwapMulti<'info> {
+    #[account(
+        seeds = [b"multi_pool", multi_pool.mints_hash.as_ref()],
+        bump = multi_pool.bump,
+    )]
+    pub multi_pool: Box<Account<'info, MultiPool>>,
+
+    pub token_program: Program<'info, Token>,
+    pub signer: Signer<'info>,
+}
+
+impl<'info> SwapMulti<'info> {
+    pub fn swap_multi(
+        &mut self,
+        index_in: u8,
+        index_out: u8,
+        amount_in: u64,
+        min_amount_out: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(amount_in > 0, AMMError::InvalidQuantity);
+        require!(index_in != index_out, AMMError::SameTokenMint);
+
+        let [reserve_in, reserve_out, user_in_account, user_out_account] = remaining_accounts
+        else {
+            return err!(AMMError::InvalidPoolAccounts);
+        };
+
+        // only the two reserves change, so prod(reserves) is kept constant by
+        // the same xy = k pricing as a pair pool
+        let reserve_in_amount = reserve_amount(&self.multi_pool, index_in as usize, reserve_in)?;
+        let reserve_out_amount = reserve_amount(&self.multi_pool, index_out as usize, reserve_out)?;
+
+        let amount_out = swap_output(
+            amount_after_fee(amount_in, self.multi_pool.fee_bps)?,
+            reserve_in_amount,
+            reserve_out_amount,
+        )?;
+        require!(amount_out >= min_amount_out, AMMError::SlippageExceeded);
+
+        transfer_to_reserve(
+            self.token_program.to_account_info(),
+            self.signer.to_account_info(),
+            user_in_account.clone(),
+            reserve_in.clone(),
+            amount_in,
+        )?;
+
+        transfer_from_reserve(
+            self.token_program.to_account_info(),
+            &self.multi_pool,
+            reserve_out.clone(),
+            user_out_account.clone(),
+            amount_out,
+        )
+    }
+}
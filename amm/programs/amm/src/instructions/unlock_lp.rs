@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{transfer, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    instructions::vesting::vested_amount,
+    states::{AMMError, LpLock},
+};
+
+// The lock account is kept after the last unlock so it stays visible as a record
+
+#[derive(Accounts)]
+pub struct UnlockLp<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"lp_lock",
+            lock.amm.as_ref(),
+            lock.locker.as_ref(),
+            &lock.lock_id.to_le_bytes()
+        ],
+        bump = lock.bump,
+        has_one = beneficiary @ AMMError::Unauthorized,
+        has_one = lp_mint,
+    )]
+    pub lock: Box<Account<'info, LpLock>>,
+
+    #[account(
+        mut,
+        seeds = [b"lp_lock_vault", lock.key().as_ref()],
+        bump,
+    )]
+    pub lock_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = beneficiary,
+        associated_token::mint = lp_mint,
+        associated_token::authority = beneficiary
+    )]
+    pub beneficiary_lp_account: Box<Account<'info, TokenAccount>>,
+
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+}
+
+impl<'info> UnlockLp<'info> {
+    pub fn unlock_lp(&mut self) -> Result<()> {
+        let vested = vested_amount(
+            self.lock.amount,
+            Clock::get()?.unix_timestamp,
+            self.lock.unlock_ts,
+            self.lock.vesting_end_ts,
+        )?;
+        let unlocked = vested.saturating_sub(self.lock.withdrawn);
+        require!(unlocked > 0, AMMError::LpLocked);
+
+        self.lock.withdrawn += unlocked;
+
+        let amm_key = self.lock.amm;
+        let locker_key = self.lock.locker;
+        let lock_id = self.lock.lock_id.to_le_bytes();
+        let seeds: &[&[u8]; 5] = &[
+            b"lp_lock",
+            amm_key.as_ref(),
+            locker_key.as_ref(),
+            &lock_id,
+            &[self.lock.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let unlock_lp_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.lock_vault.to_account_info(),
+                to: self.beneficiary_lp_account.to_account_info(),
+                authority: self.lock.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(unlock_lp_ctx, unlocked)
+    }
+}
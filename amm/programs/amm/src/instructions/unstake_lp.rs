@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+use crate::{
+    instructions::farm::{checkpoint_farm_position, settle_farm_position, update_farm_rewards},
+    states::{AMMError, Farm, FarmPosition},
+};
+
+// Accrued rewards stay in the position after unstaking and can still be harvested
+
+#[derive(Accounts)]
+pub struct UnstakeLp<'info> {
+    #[account(
+        mut,
+        seeds = [b"farm", farm.amm.as_ref()],
+        bump = farm.bump,
+        has_one = lp_mint,
+        has_one = lp_vault,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(
+        mut,
+        seeds = [b"farm_position", farm.key().as_ref(), signer.key().as_ref()],
+        bump = position.bump,
+    )]
+    pub position: Box<Account<'info, FarmPosition>>,
+
+    #[account(mut)]
+    pub lp_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = signer
+    )]
+    pub token_lp_account: Box<Account<'info, TokenAccount>>,
+
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    pub token_program: Program<'info, Token>,
+    pub signer: Signer<'info>,
+}
+
+impl<'info> UnstakeLp<'info> {
+    pub fn unstake_lp(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0, AMMError::InvalidQuantity);
+        require!(amount <= self.position.amount, AMMError::InsufficientStake);
+
+        update_farm_rewards(&mut self.farm, Clock::get()?.unix_timestamp)?;
+        settle_farm_position(&self.farm, &mut self.position)?;
+
+        let amm_key = self.farm.amm;
+        let seeds: &[&[u8]; 3] = &[b"farm", amm_key.as_ref(), &[self.farm.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        let unstake_lp_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.lp_vault.to_account_info(),
+                to: self.token_lp_account.to_account_info(),
+                authority: self.farm.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(unstake_lp_ctx, amount)?;
+
+        self.position.amount -= amount;
+        self.farm.total_staked = self
+            .farm
+            .total_staked
+            .checked_sub(amount)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+
+        checkpoint_farm_position(&self.farm, &mut self.position)
+    }
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+use crate::states::{AMMError, Config, BPS_DENOMINATOR};
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ AMMError::Unauthorized,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    pub authority: Signer<'info>,
+}
+
+impl<'info> UpdateConfig<'info> {
+    pub fn update_config(&mut self, max_referral_fee_bps: u16) -> Result<()> {
+        require!(
+            max_referral_fee_bps as u64 <= BPS_DENOMINATOR,
+            AMMError::ReferralFeeTooHigh
+        );
+
+        self.config.max_referral_fee_bps = max_referral_fee_bps;
+
+        Ok(())
+    }
+}
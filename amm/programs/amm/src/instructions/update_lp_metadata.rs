@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{
+        mpl_token_metadata::types::DataV2, update_metadata_accounts_v2, Metadata, MetadataAccount,
+        UpdateMetadataAccountsV2,
+    },
+    token::Mint,
+};
+
+use crate::states::{AMMError, AMM};
+
+#[derive(Accounts)]
+pub struct UpdateLpMetadata<'info> {
+    #[account(
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        has_one = creator @ AMMError::Unauthorized,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    /// CHECK: pool authority over token reserves and lp mint
+    #[account(
+        seeds=[b"authority", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        seeds = [b"lp_mint", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"metadata", metadata_program.key().as_ref(), lp_mint.key().as_ref()],
+        bump,
+        seeds::program = metadata_program.key(),
+    )]
+    pub lp_metadata: Box<Account<'info, MetadataAccount>>,
+
+    pub metadata_program: Program<'info, Metadata>,
+
+    pub creator: Signer<'info>,
+}
+
+impl<'info> UpdateLpMetadata<'info> {
+    pub fn update_lp_metadata(
+        &mut self,
+        name: String,
+        symbol: String,
+        uri: String,
+        bumps: &UpdateLpMetadataBumps,
+    ) -> Result<()> {
+        let token_a_mint_key = self.token_a_mint.key();
+        let token_b_mint_key = self.token_b_mint.key();
+
+        let seeds: &[&[u8]; 4] = &[
+            b"authority",
+            token_a_mint_key.as_ref(),
+            token_b_mint_key.as_ref(),
+            &[bumps.pool_authority],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let data = DataV2 {
+            name,
+            symbol,
+            uri,
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        };
+
+        let update_metadata_ctx = CpiContext::new_with_signer(
+            self.metadata_program.to_account_info(),
+            UpdateMetadataAccountsV2 {
+                metadata: self.lp_metadata.to_account_info(),
+                update_authority: self.pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        update_metadata_accounts_v2(update_metadata_ctx, None, Some(data), None, None)
+    }
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::states::{AMMError, PriceFeed};
+
+#[derive(Accounts)]
+pub struct UpdatePriceFeed<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"price_feed",
+            price_feed.authority.as_ref(),
+            &price_feed.feed_id.to_le_bytes()
+        ],
+        bump = price_feed.bump,
+        has_one = authority @ AMMError::Unauthorized,
+    )]
+    pub price_feed: Box<Account<'info, PriceFeed>>,
+
+    pub authority: Signer<'info>,
+}
+
+impl<'info> UpdatePriceFeed<'info> {
+    pub fn update_price_feed(&mut self, price: u64) -> Result<()> {
+        require!(price > 0, AMMError::InvalidQuantity);
+
+        self.price_feed.price = price;
+        self.price_feed.publish_time = Clock::get()?.unix_timestamp;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,271 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{
+        allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+    },
+};
+
+use crate::states::{AMMError, BPS_DENOMINATOR};
+
+// Math and account helpers shared across instructions. Feature-specific
+// helpers live in modules next to their instructions.
+// All pool math widens u64 token amounts to u128 before multiplying, since the
+// product of two u64 values always fits in u128. Results are narrowed back to
+// u64 only at the end through a checked conversion.
+
+pub fn to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| error!(AMMError::ArithmeticOverflow))
+}
+
+pub fn swap_output(amount_in: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+    // amountOut = (reserveOut * amountIn) / (reserveIn + amountIn)
+    let numerator = (reserve_out as u128)
+        .checked_mul(amount_in as u128)
+        .ok_or(AMMError::ArithmeticOverflow)?;
+    let denominator = (reserve_in as u128)
+        .checked_add(amount_in as u128)
+        .ok_or(AMMError::ArithmeticOverflow)?;
+
+    let amount_out = numerator
+        .checked_div(denominator)
+        .ok_or(AMMError::ArithmeticOverflow)?;
+
+    to_u64(amount_out)
+}
+
+pub fn initial_lp_tokens(
+    quantity_a: u64,
+    quantity_b: u64,
+    decimals_a: u8,
+    decimals_b: u8,
+    lp_decimals: u8,
+) -> Result<u64> {
+    // LP[minted] = Sqrt(qA X qB) in whole tokens, scaled to lp_decimals
+    // LP = Sqrt(qA / 10^dA X qB / 10^dB) X 10^dLP = Sqrt(qA X qB X 10^(2dLP - dA - dB))
+    // if the scaled product overflows u128 its sqrt cannot fit in a u64 either
+    let value = (quantity_a as u128)
+        .checked_mul(quantity_b as u128)
+        .ok_or(AMMError::ArithmeticOverflow)?;
+
+    let exponent = 2 * lp_decimals as i32 - decimals_a as i32 - decimals_b as i32;
+    let scale = 10u128
+        .checked_pow(exponent.unsigned_abs())
+        .ok_or(AMMError::ArithmeticOverflow)?;
+
+    let value = if exponent >= 0 {
+        value.checked_mul(scale)
+    } else {
+        value.checked_div(scale)
+    }
+    .ok_or(AMMError::ArithmeticOverflow)?;
+
+    Ok(binary_search_sqrt(value))
+}
+
+pub fn proportional_lp_tokens(quantity: u64, reserve: u64, lp_supply: u64) -> Result<u64> {
+    // LP[minted] = q/v X LP[total]
+    let lp_tokens = (lp_supply as u128)
+        .checked_mul(quantity as u128)
+        .and_then(|v| v.checked_div(reserve as u128))
+        .ok_or(AMMError::ArithmeticOverflow)?;
+
+    to_u64(lp_tokens)
+}
+
+pub fn is_proportional_deposit(
+    quantity_a: u64,
+    quantity_b: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+) -> bool {
+    // qA / qB == vA / vB  <=>  qA X vB == qB X vA
+    (quantity_a as u128) * (reserve_b as u128) == (quantity_b as u128) * (reserve_a as u128)
+}
+
+pub fn withdraw_amount(lp_token_quantity: u64, reserve: u64, lp_supply: u64) -> Result<u64> {
+    // amount = (shares * bal) / totalSupply
+    let amount = (lp_token_quantity as u128)
+        .checked_mul(reserve as u128)
+        .and_then(|v| v.checked_div(lp_supply as u128))
+        .ok_or(AMMError::ArithmeticOverflow)?;
+
+    to_u64(amount)
+}
+
+pub fn binary_search_sqrt(value: u128) -> u64 {
+    if value < 2 {
+        return value as u64;
+    }
+
+    // sqrt of a u128 always fits in a u64, so bound the search there and
+    // compare with a division to keep mid * mid from overflowing
+    let mut left: u128 = 1;
+    let mut right: u128 = value.min(u64::MAX as u128);
+    let mut ans: u128 = 0;
+
+    while left <= right {
+        let mid = left + (right - left) / 2;
+        if mid <= value / mid {
+            ans = mid;
+            left = mid + 1;
+        } else {
+            right = mid - 1;
+        }
+    }
+    ans as u64
+}
+
+pub fn single_sided_swap_amount(amount_in: u64, reserve_in: u64, fee_bps: u16) -> Result<u64> {
+    // Swapping s of amount a leaves the LP holding (a - s) and the swap output in
+    // the same ratio as the post-swap reserves. For xy = k with g = 1 - fee:
+    // s = (Sqrt(h^2 + g X a X r) - h) / g, where h = (1 + g) X r / 2
+    // without a fee this is s = Sqrt(r X (r + a)) - r
+    // h <= r and g X a X r <= a X r, so the sum stays below r X (r + a) which
+    // fits in u128 since r + a is the post-deposit reserve balance
+    let g = (BPS_DENOMINATOR - fee_bps as u64) as u128;
+    let reserve = reserve_in as u128;
+    require!(
+        reserve_in.checked_add(amount_in).is_some(),
+        AMMError::ArithmeticOverflow
+    );
+
+    let h = (BPS_DENOMINATOR as u128 + g) * reserve / (2 * BPS_DENOMINATOR as u128);
+    let value = h * h + (amount_in as u128 * g / BPS_DENOMINATOR as u128) * reserve;
+
+    let root = binary_search_sqrt(value) as u128;
+    let swap_amount =
+        root.checked_sub(h).ok_or(AMMError::ArithmeticOverflow)? * BPS_DENOMINATOR as u128 / g;
+
+    // rounding can never push the swap past the whole input
+    Ok(to_u64(swap_amount)?.min(amount_in))
+}
+
+// Q64.64 fixed point
+pub const Q64: u128 = 1 << 64;
+
+// a X b for Q64.64 values, split into 64-bit halves so no partial product overflows
+pub fn mul_q64(a: u128, b: u128) -> Option<u128> {
+    let (a_hi, a_lo) = (a >> 64, a & (Q64 - 1));
+    let (b_hi, b_lo) = (b >> 64, b & (Q64 - 1));
+
+    let hi = a_hi * b_hi;
+    if hi >= Q64 {
+        return None;
+    }
+
+    (hi << 64)
+        .checked_add(a_hi * b_lo)?
+        .checked_add(a_lo * b_hi)?
+        .checked_add((a_lo * b_lo) >> 64)
+}
+
+// Creates a PDA the way Anchor's `init` does. Anyone can send lamports to an
+// address before it exists, which would make a plain create_account fail, so
+// a pre-funded account is topped up to rent exemption, allocated and assigned
+// instead.
+pub fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let lamports = account.lamports();
+
+    if lamports == 0 {
+        let create_account_ctx = CpiContext::new_with_signer(
+            system_program.clone(),
+            CreateAccount {
+                from: payer.clone(),
+                to: account.clone(),
+            },
+            signer_seeds,
+        );
+
+        return create_account(create_account_ctx, rent, space as u64, owner);
+    }
+
+    let top_up = rent.saturating_sub(lamports);
+    if top_up > 0 {
+        let top_up_ctx = CpiContext::new(
+            system_program.clone(),
+            Transfer {
+                from: payer.clone(),
+                to: account.clone(),
+            },
+        );
+
+        transfer(top_up_ctx, top_up)?;
+    }
+
+    let allocate_ctx = CpiContext::new_with_signer(
+        system_program.clone(),
+        Allocate {
+            account_to_allocate: account.clone(),
+        },
+        signer_seeds,
+    );
+
+    allocate(allocate_ctx, space as u64)?;
+
+    let assign_ctx = CpiContext::new_with_signer(
+        system_program.clone(),
+        Assign {
+            account_to_assign: account.clone(),
+        },
+        signer_seeds,
+    );
+
+    assign(assign_ctx, owner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overflow() -> Error {
+        AMMError::ArithmeticOverflow.into()
+    }
+
+    #[test]
+    fn swap_output_at_max_reserves() {
+        // the output is bounded by reserve_out, so even maxed reserves fit
+        assert_eq!(
+            swap_output(u64::MAX, u64::MAX, u64::MAX).unwrap(),
+            u64::MAX / 2
+        );
+        assert_eq!(swap_output(u64::MAX, 0, u64::MAX).unwrap(), u64::MAX);
+        assert_eq!(swap_output(0, 0, u64::MAX).unwrap_err(), overflow());
+    }
+
+    #[test]
+    fn withdraw_amount_at_max_reserves() {
+        assert_eq!(
+            withdraw_amount(u64::MAX, u64::MAX, u64::MAX).unwrap(),
+            u64::MAX
+        );
+        assert_eq!(
+            withdraw_amount(u64::MAX, u64::MAX, 1).unwrap_err(),
+            overflow()
+        );
+        assert_eq!(withdraw_amount(1, u64::MAX, 0).unwrap_err(), overflow());
+    }
+
+    #[test]
+    fn proportional_lp_tokens_at_max_reserves() {
+        assert_eq!(
+            proportional_lp_tokens(u64::MAX, u64::MAX, u64::MAX).unwrap(),
+            u64::MAX
+        );
+        assert_eq!(
+            proportional_lp_tokens(u64::MAX, 1, u64::MAX).unwrap_err(),
+            overflow()
+        );
+        assert_eq!(
+            proportional_lp_tokens(1, 0, u64::MAX).unwrap_err(),
+            overflow()
+        );
+    }
+}
@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+use crate::{instructions::utils::to_u64, states::AMMError};
+
+// Nothing unlocks before unlock_ts, then the lock vests linearly until vesting_end_ts
+// vested = amount * (now - unlockTs) / (vestingEndTs - unlockTs)
+pub fn vested_amount(amount: u64, now: i64, unlock_ts: i64, vesting_end_ts: i64) -> Result<u64> {
+    if now < unlock_ts {
+        return Ok(0);
+    }
+    if now >= vesting_end_ts {
+        return Ok(amount);
+    }
+
+    let vested = (amount as u128)
+        .checked_mul((now - unlock_ts) as u128)
+        .ok_or(AMMError::ArithmeticOverflow)?
+        / (vesting_end_ts - unlock_ts) as u128;
+
+    to_u64(vested)
+}
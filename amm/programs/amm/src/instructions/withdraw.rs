@@ -1,9 +1,20 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{
-    burn, close_account, transfer, Burn, CloseAccount, Mint, Token, TokenAccount, Transfer,
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{
+        burn, close_account, transfer, Burn, CloseAccount, Mint, Token, TokenAccount, Transfer,
+    },
 };
 
-use crate::states::{AMMError, AMM};
+use crate::{
+    instructions::{
+        allowlist::check_allowlist,
+        lp_fees::settle_lp_fees,
+        native_sol::{is_native_a, unwrap_sol},
+        utils::withdraw_amount,
+    },
+    states::{AMMError, AllowlistEntry, LpFeeCheckpoint, AMM},
+};
 
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
@@ -15,14 +26,16 @@ pub struct Withdraw<'info> {
     pub amm: AccountLoader<'info, AMM>,
 
     #[account(
-        mut,
+        init_if_needed,
+        payer = signer,
         associated_token::mint = token_a_mint,
         associated_token::authority = signer
     )]
     pub token_a_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
-        mut,
+        init_if_needed,
+        payer = signer,
         associated_token::mint = token_b_mint,
         associated_token::authority = signer
     )]
@@ -35,6 +48,14 @@ pub struct Withdraw<'info> {
     )]
     pub token_lp_account: Box<Account<'info, TokenAccount>>,
 
+    // opt-in fee tracking, see create_lp_fee_checkpoint
+    #[account(
+        mut,
+        seeds = [b"lp_fees", amm.key().as_ref(), signer.key().as_ref()],
+        bump = lp_fee_checkpoint.bump,
+    )]
+    pub lp_fee_checkpoint: Option<Box<Account<'info, LpFeeCheckpoint>>>,
+
     #[account(
         mut,
         seeds = [b"reserve_a", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
@@ -69,7 +90,22 @@ pub struct Withdraw<'info> {
     )]
     pub lp_mint: Box<Account<'info, Mint>>,
 
+    // permissioned pools need one of these
+    #[account(
+        seeds = [b"allowlist", amm.key().as_ref(), signer.key().as_ref()],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Option<Box<Account<'info, AllowlistEntry>>>,
+    pub allowlist_authority: Option<Signer<'info>>,
+
+    // temporary account the `_sol` variant unwraps its wSOL output through
+    #[account(mut, seeds = [b"wsol", signer.key().as_ref()], bump)]
+    /// CHECK: created and closed by unwrap_sol within the instruction
+    pub temp_wsol_account: Option<UncheckedAccount<'info>>,
+
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -77,19 +113,29 @@ pub struct Withdraw<'info> {
 
 impl<'info> Withdraw<'info> {
     pub fn withdraw(&mut self, lp_token_quantity: u64, bumps: &WithdrawBumps) -> Result<()> {
+        check_allowlist(
+            &*self.amm.load()?,
+            self.allowlist_entry.as_deref(),
+            self.allowlist_authority.as_ref(),
+        )?;
+
         // amount_0 = (shares * bal0) / totalSupply
         // amount_1 = (shares * bal1) / totalSupply
 
+        require!(lp_token_quantity > 0, AMMError::InvalidQuantity);
+
         let amm = self.amm.load()?;
-        let token_a_to_release = lp_token_quantity
-            .checked_mul(self.reserve_a.amount)
-            .and_then(|v| v.checked_div(amm.lp_supply))
-            .ok_or(AMMError::ArithmeticOverflow)?;
+        let token_a_to_release =
+            withdraw_amount(lp_token_quantity, self.reserve_a.amount, amm.lp_supply)?;
+        let token_b_to_release =
+            withdraw_amount(lp_token_quantity, self.reserve_b.amount, amm.lp_supply)?;
 
-        let token_b_to_release = lp_token_quantity
-            .checked_mul(self.reserve_b.amount)
-            .and_then(|v| v.checked_div(amm.lp_supply))
-            .ok_or(AMMError::ArithmeticOverflow)?;
+        // fees are settled on the LP balance held before this withdrawal, and
+        // the LP tokens burned here stop earning
+        if let Some(checkpoint) = self.lp_fee_checkpoint.as_deref_mut() {
+            settle_lp_fees(&amm, checkpoint, self.token_lp_account.amount)?;
+            checkpoint.lp_balance = checkpoint.lp_balance.saturating_sub(lp_token_quantity);
+        }
         drop(amm);
 
         let lp_token_amount = self.token_lp_account.amount;
@@ -106,7 +152,10 @@ impl<'info> Withdraw<'info> {
         burn(burn_lp_tokens_ctx, lp_token_quantity)?;
 
         let mut amm = self.amm.load_mut()?;
-        amm.lp_supply -= lp_token_quantity;
+        amm.lp_supply = amm
+            .lp_supply
+            .checked_sub(lp_token_quantity)
+            .ok_or(AMMError::ArithmeticOverflow)?;
         drop(amm);
 
         let token_a_mint_key = self.token_a_mint.key();
@@ -159,4 +208,51 @@ impl<'info> Withdraw<'info> {
 
         Ok(())
     }
+
+    fn native_accounts(
+        &mut self,
+    ) -> Result<(&mut Box<Account<'info, TokenAccount>>, AccountInfo<'info>)> {
+        if is_native_a(&self.token_a_mint.key(), &self.token_b_mint.key())? {
+            Ok((
+                &mut self.token_a_account,
+                self.token_a_mint.to_account_info(),
+            ))
+        } else {
+            Ok((
+                &mut self.token_b_account,
+                self.token_b_mint.to_account_info(),
+            ))
+        }
+    }
+
+    // wSOL the signer holds before the instruction runs
+    pub fn native_balance(&mut self) -> Result<u64> {
+        Ok(self.native_accounts()?.0.amount)
+    }
+
+    // Unwraps only what the instruction added on top of `balance_before`
+    pub fn unwrap_native(&mut self, balance_before: u64, bumps: &WithdrawBumps) -> Result<()> {
+        let system_program = self.system_program.to_account_info();
+        let token_program = self.token_program.to_account_info();
+        let signer = self.signer.to_account_info();
+        let temp_wsol_account = self
+            .temp_wsol_account
+            .as_ref()
+            .ok_or(AMMError::MissingTempWsolAccount)?
+            .to_account_info();
+
+        let (wsol_account, native_mint) = self.native_accounts()?;
+        wsol_account.reload()?;
+        let added = wsol_account.amount.saturating_sub(balance_before);
+
+        unwrap_sol(
+            system_program,
+            token_program,
+            signer,
+            wsol_account.to_account_info(),
+            (temp_wsol_account, bumps.temp_wsol_account),
+            native_mint,
+            added,
+        )
+    }
 }
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{burn, Burn, Mint, Token, TokenAccount};
+
+use crate::{
+    instructions::{
+        multi_pool::{reserve_amount, transfer_from_reserve},
+        utils::withdraw_amount,
+    },
+    states::{AMMError, MultiPool},
+};
+
+// remaining_accounts: [reserve_0 .. reserve_n-1, user_account_0 .. user_account_n-1]
+
+#[derive(Accounts)]
+pub struct WithdrawMulti<'info> {
+    #[account(
+        mut,
+        seeds = [b"multi_pool", multi_pool.mints_hash.as_ref()],
+        bump = multi_pool.bump,
+        has_one = lp_mint,
+    )]
+    pub multi_pool: Box<Account<'info, MultiPool>>,
+
+    #[account(mut)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = signer
+    )]
+    pub token_lp_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub signer: Signer<'info>,
+}
+
+impl<'info> WithdrawMulti<'info> {
+    pub fn withdraw_multi(
+        &mut self,
+        lp_token_quantity: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(lp_token_quantity > 0, AMMError::InvalidQuantity);
+
+        let token_count = self.multi_pool.token_count as usize;
+        require!(
+            remaining_accounts.len() == 2 * token_count,
+            AMMError::InvalidPoolAccounts
+        );
+
+        // amount_i = (shares * bal_i) / totalSupply
+        let (reserves, user_accounts) = remaining_accounts.split_at(token_count);
+        let lp_supply = self.multi_pool.lp_supply;
+        let amounts = reserves
+            .iter()
+            .enumerate()
+            .map(|(i, reserve)| {
+                let reserve = reserve_amount(&self.multi_pool, i, reserve)?;
+                withdraw_amount(lp_token_quantity, reserve, lp_supply)
+            })
+            .collect::<Result<Vec<u64>>>()?;
+
+        let burn_lp_tokens_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.lp_mint.to_account_info(),
+                from: self.token_lp_account.to_account_info(),
+                authority: self.signer.to_account_info(),
+            },
+        );
+
+        burn(burn_lp_tokens_ctx, lp_token_quantity)?;
+
+        self.multi_pool.lp_supply = lp_supply
+            .checked_sub(lp_token_quantity)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+
+        for ((reserve, user_account), &amount) in reserves.iter().zip(user_accounts).zip(&amounts) {
+            transfer_from_reserve(
+                self.token_program.to_account_info(),
+                &self.multi_pool,
+                reserve.clone(),
+                user_account.clone(),
+                amount,
+            )?;
+        }
+
+        Ok(())
+    }
+}
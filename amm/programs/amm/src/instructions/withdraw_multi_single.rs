@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{burn, Burn, Mint, Token, TokenAccount};
+
+use crate::{
+    instructions::multi_pool::{
+        multi_single_withdraw_amount, reserve_amount, transfer_from_reserve,
+    },
+    states::{AMMError, MultiPool},
+};
+
+// remaining_accounts: [reserve, user_account] for the withdrawn token
+
+#[derive(Accounts)]
+pub struct WithdrawMultiSingle<'info> {
+    #[account(
+        mut,
+        seeds = [b"multi_pool", multi_pool.mints_hash.as_ref()],
+        bump = multi_pool.bump,
+        has_one = lp_mint,
+    )]
+    pub multi_pool: Box<Account<'info, MultiPool>>,
+
+    #[account(mut)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = signer
+    )]
+    pub token_lp_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub signer: Signer<'info>,
+}
+
+impl<'info> WithdrawMultiSingle<'info> {
+    pub fn withdraw_multi_single(
+        &mut self,
+        index: u8,
+        lp_token_quantity: u64,
+        min_amount_out: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(lp_token_quantity > 0, AMMError::InvalidQuantity);
+
+        // the last LP would drain a single reserve and leave the others stranded
+        let lp_supply = self.multi_pool.lp_supply;
+        require!(lp_token_quantity < lp_supply, AMMError::InvalidLiquidity);
+
+        let [reserve, user_account] = remaining_accounts else {
+            return err!(AMMError::InvalidPoolAccounts);
+        };
+
+        // amount = r X (1 - ((LP[total] - LP) / LP[total])^n)
+        // the inverse of a single-sided deposit
+        let reserve_before = reserve_amount(&self.multi_pool, index as usize, reserve)?;
+        let amount_out = multi_single_withdraw_amount(
+            lp_token_quantity,
+            reserve_before,
+            lp_supply,
+            self.multi_pool.token_count,
+            self.multi_pool.fee_bps,
+        )?;
+        require!(amount_out >= min_amount_out, AMMError::SlippageExceeded);
+
+        let burn_lp_tokens_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.lp_mint.to_account_info(),
+                from: self.token_lp_account.to_account_info(),
+                authority: self.signer.to_account_info(),
+            },
+        );
+
+        burn(burn_lp_tokens_ctx, lp_token_quantity)?;
+
+        self.multi_pool.lp_supply = lp_supply - lp_token_quantity;
+
+        transfer_from_reserve(
+            self.token_program.to_account_info(),
+            &self.multi_pool,
+            reserve.clone(),
+            user_account.clone(),
+            amount_out,
+        )
+    }
+}
@@ -0,0 +1,208 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{
+        burn, close_account, transfer, Burn, CloseAccount, Mint, Token, TokenAccount, Transfer,
+    },
+};
+
+use crate::{
+    instructions::{
+        allowlist::check_allowlist, position::settle_position_fees, utils::withdraw_amount,
+    },
+    states::{AMMError, AllowlistEntry, Position, AMM},
+};
+
+// Whoever holds the position NFT can withdraw some or all of its shares.
+// Emptying the position burns the NFT and closes the position account.
+
+#[derive(Accounts)]
+pub struct WithdrawPosition<'info> {
+    #[account(
+        mut,
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        mut,
+        seeds = [b"position", position_mint.key().as_ref()],
+        bump = position.bump,
+        has_one = amm,
+        has_one = position_mint,
+    )]
+    pub position: Box<Account<'info, Position>>,
+
+    #[account(mut)]
+    pub position_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = position_mint,
+        token::authority = signer,
+        constraint = position_token_account.amount == 1 @ AMMError::NotPositionHolder,
+    )]
+    pub position_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = token_a_mint,
+        associated_token::authority = signer
+    )]
+    pub token_a_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = token_b_mint,
+        associated_token::authority = signer
+    )]
+    pub token_b_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve_a", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = pool_authority
+    )]
+    pub reserve_a: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"reserve_b", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = pool_authority
+    )]
+    pub reserve_b: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: pool authority over token reserves and lp mint
+    #[account(
+        seeds=[b"authority", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    // permissioned pools need one of these
+    #[account(
+        seeds = [b"allowlist", amm.key().as_ref(), signer.key().as_ref()],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Option<Box<Account<'info, AllowlistEntry>>>,
+    pub allowlist_authority: Option<Signer<'info>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> WithdrawPosition<'info> {
+    pub fn withdraw_position(&mut self, shares: u64, bumps: &WithdrawPositionBumps) -> Result<()> {
+        check_allowlist(
+            &*self.amm.load()?,
+            self.allowlist_entry.as_deref(),
+            self.allowlist_authority.as_ref(),
+        )?;
+
+        require!(shares > 0, AMMError::InvalidQuantity);
+        require!(shares <= self.position.shares, AMMError::InsufficientShares);
+
+        let mut amm = self.amm.load_mut()?;
+        let reserve_a = self.reserve_a.amount;
+        let reserve_b = self.reserve_b.amount;
+
+        // bring the position's fees up to date before its shares change
+        let position = &mut self.position;
+        settle_position_fees(&amm, position)?;
+
+        let token_a_to_release = withdraw_amount(shares, reserve_a, amm.lp_supply)?;
+        let token_b_to_release = withdraw_amount(shares, reserve_b, amm.lp_supply)?;
+
+        amm.lp_supply = amm
+            .lp_supply
+            .checked_sub(shares)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+        drop(amm);
+
+        position.shares -= shares;
+        position.withdrawn_a = position
+            .withdrawn_a
+            .checked_add(token_a_to_release)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+        position.withdrawn_b = position
+            .withdrawn_b
+            .checked_add(token_b_to_release)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+
+        let token_a_mint_key = self.token_a_mint.key();
+        let token_b_mint_key = self.token_b_mint.key();
+
+        let seeds: &[&[u8]; 4] = &[
+            b"authority",
+            token_a_mint_key.as_ref(),
+            token_b_mint_key.as_ref(),
+            &[bumps.pool_authority],
+        ];
+
+        let signer_seeds = &[&seeds[..]];
+
+        let release_token_a_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.reserve_a.to_account_info(),
+                to: self.token_a_account.to_account_info(),
+                authority: self.pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        let release_token_b_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.reserve_b.to_account_info(),
+                to: self.token_b_account.to_account_info(),
+                authority: self.pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(release_token_a_ctx, token_a_to_release)?;
+        transfer(release_token_b_ctx, token_b_to_release)?;
+
+        if self.position.shares == 0 {
+            let burn_position_ctx = CpiContext::new(
+                self.token_program.to_account_info(),
+                Burn {
+                    mint: self.position_mint.to_account_info(),
+                    from: self.position_token_account.to_account_info(),
+                    authority: self.signer.to_account_info(),
+                },
+            );
+
+            burn(burn_position_ctx, 1)?;
+
+            let close_position_account_ctx = CpiContext::new(
+                self.token_program.to_account_info(),
+                CloseAccount {
+                    account: self.position_token_account.to_account_info(),
+                    destination: self.signer.to_account_info(),
+                    authority: self.signer.to_account_info(),
+                },
+            );
+
+            close_account(close_position_account_ctx)?;
+
+            self.position.close(self.signer.to_account_info())?;
+        }
+
+        Ok(())
+    }
+}
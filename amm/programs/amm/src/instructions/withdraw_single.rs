@@ -0,0 +1,274 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{
+        burn, close_account, transfer, Burn, CloseAccount, Mint, Token, TokenAccount, Transfer,
+    },
+};
+
+use crate::{
+    instructions::{
+        allowlist::check_allowlist,
+        circuit_breaker::check_circuit_breaker,
+        dynamic_fee::{amount_after_fee, update_swap_fee},
+        lp_fees::{accrue_lp_fee, settle_lp_fees},
+        oracle::check_oracle_price,
+        utils::{swap_output, withdraw_amount},
+    },
+    states::{AMMError, AllowlistEntry, LpFeeCheckpoint, PriceFeed, AMM},
+};
+
+#[derive(Accounts)]
+pub struct WithdrawSingle<'info> {
+    #[account(
+        mut,
+        seeds=[b"amm", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub amm: AccountLoader<'info, AMM>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = token_a_mint,
+        associated_token::authority = signer
+    )]
+    pub token_a_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = token_b_mint,
+        associated_token::authority = signer
+    )]
+    pub token_b_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = signer
+    )]
+    pub token_lp_account: Box<Account<'info, TokenAccount>>,
+
+    // opt-in fee tracking, see create_lp_fee_checkpoint
+    #[account(
+        mut,
+        seeds = [b"lp_fees", amm.key().as_ref(), signer.key().as_ref()],
+        bump = lp_fee_checkpoint.bump,
+    )]
+    pub lp_fee_checkpoint: Option<Box<Account<'info, LpFeeCheckpoint>>>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve_a", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_a_mint,
+        token::authority = pool_authority
+    )]
+    pub reserve_a: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"reserve_b", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+        token::mint = token_b_mint,
+        token::authority = pool_authority
+    )]
+    pub reserve_b: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: pool authority over token reserves and lp mint
+    #[account(
+        seeds=[b"authority", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"lp_mint", token_a_mint.key().as_ref(), token_b_mint.key().as_ref()],
+        bump,
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    // required when the pool has an oracle configured
+    pub oracle: Option<Account<'info, PriceFeed>>,
+
+    // permissioned pools need one of these
+    #[account(
+        seeds = [b"allowlist", amm.key().as_ref(), signer.key().as_ref()],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Option<Box<Account<'info, AllowlistEntry>>>,
+    pub allowlist_authority: Option<Signer<'info>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> WithdrawSingle<'info> {
+    pub fn withdraw_single(
+        &mut self,
+        lp_token_quantity: u64,
+        is_a: bool,
+        min_amount_out: u64,
+        bumps: &WithdrawSingleBumps,
+    ) -> Result<()> {
+        check_allowlist(
+            &*self.amm.load()?,
+            self.allowlist_entry.as_deref(),
+            self.allowlist_authority.as_ref(),
+        )?;
+
+        require!(lp_token_quantity > 0, AMMError::InvalidQuantity);
+
+        let (reserve_out, reserve_other, user_out_account) = if is_a {
+            (&self.reserve_a, &self.reserve_b, &self.token_a_account)
+        } else {
+            (&self.reserve_b, &self.reserve_a, &self.token_b_account)
+        };
+
+        // ZAP OUT
+        // 1. withdraw both sides proportionally
+        // 2. swap the other side back into the pool at the post-withdrawal reserves
+        // the other side never leaves the pool, so only the requested token is transferred
+
+        // the last LP has no reserves left to swap against
+        let mut amm = self.amm.load_mut()?;
+        require!(amm.batch_window_slots == 0, AMMError::BatchModeActive);
+        require!(
+            lp_token_quantity < amm.lp_supply,
+            AMMError::InvalidLiquidity
+        );
+
+        // the swap back pays the swap fee like any other trade
+        let now = Clock::get()?.unix_timestamp;
+        let fee_bps = update_swap_fee(&mut amm, self.reserve_a.amount, self.reserve_b.amount, now);
+
+        let withdrawn_out = withdraw_amount(lp_token_quantity, reserve_out.amount, amm.lp_supply)?;
+        let withdrawn_other =
+            withdraw_amount(lp_token_quantity, reserve_other.amount, amm.lp_supply)?;
+
+        let withdrawn_other_after_fee = amount_after_fee(withdrawn_other, fee_bps)?;
+        let swap_out = swap_output(
+            withdrawn_other_after_fee,
+            reserve_other.amount - withdrawn_other,
+            reserve_out.amount - withdrawn_out,
+        )?;
+
+        let amount_out = withdrawn_out
+            .checked_add(swap_out)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+        require!(amount_out >= min_amount_out, AMMError::SlippageExceeded);
+
+        // the other side is swapped straight back, so only reserve_out changes
+        let reserve_out_after = reserve_out.amount - amount_out;
+        let (reserve_a_after, reserve_b_after) = if is_a {
+            (reserve_out_after, reserve_other.amount)
+        } else {
+            (reserve_other.amount, reserve_out_after)
+        };
+        check_oracle_price(
+            &amm,
+            self.oracle.as_ref(),
+            reserve_a_after,
+            reserve_b_after,
+            self.token_a_mint.decimals,
+            self.token_b_mint.decimals,
+            now,
+        )?;
+
+        // the swap leg counts toward the price limit - the payout is the LP's own
+        // liquidity, not swap output
+        check_circuit_breaker(
+            &mut amm,
+            self.reserve_a.amount,
+            self.reserve_b.amount,
+            reserve_a_after,
+            reserve_b_after,
+            (0, 0),
+            Clock::get()?.slot,
+        )?;
+
+        // fees are settled on the LP balance held before this withdrawal
+        if let Some(checkpoint) = self.lp_fee_checkpoint.as_deref_mut() {
+            settle_lp_fees(&amm, checkpoint, self.token_lp_account.amount)?;
+        }
+
+        // the swap leg's fee is left in the other token's reserve. The
+        // checkpoint moves past it so the withdrawer's balance isn't credited
+        // with a share of their own fee, and the LP tokens burned here stop
+        // earning
+        accrue_lp_fee(&mut amm, withdrawn_other - withdrawn_other_after_fee, !is_a);
+        if let Some(checkpoint) = self.lp_fee_checkpoint.as_deref_mut() {
+            checkpoint.fee_growth_a = amm.fee_growth_a;
+            checkpoint.fee_growth_b = amm.fee_growth_b;
+            checkpoint.lp_balance = checkpoint.lp_balance.saturating_sub(lp_token_quantity);
+        }
+        drop(amm);
+
+        let lp_token_amount = self.token_lp_account.amount;
+
+        let burn_lp_tokens_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.lp_mint.to_account_info(),
+                from: self.token_lp_account.to_account_info(),
+                authority: self.signer.to_account_info(),
+            },
+        );
+
+        burn(burn_lp_tokens_ctx, lp_token_quantity)?;
+
+        let mut amm = self.amm.load_mut()?;
+        amm.lp_supply = amm
+            .lp_supply
+            .checked_sub(lp_token_quantity)
+            .ok_or(AMMError::ArithmeticOverflow)?;
+        drop(amm);
+
+        let token_a_mint_key = self.token_a_mint.key();
+        let token_b_mint_key = self.token_b_mint.key();
+
+        let seeds: &[&[u8]; 4] = &[
+            b"authority",
+            token_a_mint_key.as_ref(),
+            token_b_mint_key.as_ref(),
+            &[bumps.pool_authority],
+        ];
+
+        let signer_seeds = &[&seeds[..]];
+
+        let release_token_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: reserve_out.to_account_info(),
+                to: user_out_account.to_account_info(),
+                authority: self.pool_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        transfer(release_token_ctx, amount_out)?;
+
+        if lp_token_quantity == lp_token_amount {
+            let close_lp_account_ctx = CpiContext::new(
+                self.token_program.to_account_info(),
+                CloseAccount {
+                    account: self.token_lp_account.to_account_info(),
+                    destination: self.signer.to_account_info(),
+                    authority: self.signer.to_account_info(),
+                },
+            );
+
+            close_account(close_lp_account_ctx)?;
+        }
+
+        Ok(())
+    }
+}
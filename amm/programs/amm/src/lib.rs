@@ -2,10 +2,12 @@ use anchor_lang::prelude::*;
 
 declare_id!("5GwvY98CgoPByjWEz2ZL6yK2J7oZvKNySrMSSR3E4RU4");
 
+pub mod cpi_helpers;
 mod instructions;
-mod states;
+pub mod states;
 
 use crate::instructions::*;
+use crate::states::EarnedFees;
 
 // MATH - https://medium.com/@tomarpari90/constant-product-automated-market-maker-everything-you-need-to-know-5bfeb0251ef2
 
@@ -14,18 +16,400 @@ pub mod amm {
     use super::*;
 
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
-        ctx.accounts.initialize_amm_pool(&ctx.bumps)
+        ctx.accounts.initialize_amm_pool(&ctx.bumps)?;
+        ctx.accounts.create_lp_metadata(&ctx.bumps)
     }
 
     pub fn deposit(ctx: Context<Deposit>, quantity_a: u64, quantity_b: u64) -> Result<()> {
         ctx.accounts.deposit(quantity_a, quantity_b, &ctx.bumps)
     }
 
+    pub fn deposit_sol(ctx: Context<Deposit>, quantity_a: u64, quantity_b: u64) -> Result<()> {
+        ctx.accounts
+            .wrap_native(quantity_a, quantity_b, &ctx.bumps)?;
+        ctx.accounts.deposit(quantity_a, quantity_b, &ctx.bumps)?;
+        ctx.accounts.close_wrapped_native()
+    }
+
+    pub fn deposit_single(
+        ctx: Context<DepositSingle>,
+        amount_in: u64,
+        is_a: bool,
+        min_lp_out: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .deposit_single(amount_in, is_a, min_lp_out, &ctx.bumps)
+    }
+
+    pub fn deposit_position(
+        ctx: Context<DepositPosition>,
+        quantity_a: u64,
+        quantity_b: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .deposit_position(quantity_a, quantity_b, &ctx.bumps)
+    }
+
     pub fn swap(ctx: Context<Swap>, quantity: u64, is_a: bool) -> Result<()> {
         ctx.accounts.swap(quantity, is_a, &ctx.bumps)
     }
 
+    pub fn swap_sol(ctx: Context<Swap>, quantity: u64, is_a: bool) -> Result<()> {
+        let wsol_before = ctx.accounts.native_balance()?;
+        ctx.accounts.wrap_native(quantity, is_a, &ctx.bumps)?;
+        ctx.accounts.swap(quantity, is_a, &ctx.bumps)?;
+        ctx.accounts.unwrap_native(wsol_before, &ctx.bumps)
+    }
+
     pub fn withdraw(ctx: Context<Withdraw>, lp_token_quantity: u64) -> Result<()> {
         ctx.accounts.withdraw(lp_token_quantity, &ctx.bumps)
     }
+
+    pub fn withdraw_sol(ctx: Context<Withdraw>, lp_token_quantity: u64) -> Result<()> {
+        let wsol_before = ctx.accounts.native_balance()?;
+        ctx.accounts.withdraw(lp_token_quantity, &ctx.bumps)?;
+        ctx.accounts.unwrap_native(wsol_before, &ctx.bumps)
+    }
+
+    pub fn withdraw_position(ctx: Context<WithdrawPosition>, shares: u64) -> Result<()> {
+        ctx.accounts.withdraw_position(shares, &ctx.bumps)
+    }
+
+    pub fn withdraw_single(
+        ctx: Context<WithdrawSingle>,
+        lp_token_quantity: u64,
+        is_a: bool,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .withdraw_single(lp_token_quantity, is_a, min_amount_out, &ctx.bumps)
+    }
+
+    pub fn create_lp_fee_checkpoint(ctx: Context<CreateLpFeeCheckpoint>) -> Result<()> {
+        ctx.accounts.create_lp_fee_checkpoint(&ctx.bumps)
+    }
+
+    pub fn quote_earned_fees(ctx: Context<QuoteEarnedFees>) -> Result<EarnedFees> {
+        ctx.accounts.quote_earned_fees()
+    }
+
+    pub fn quote_position_fees(ctx: Context<QuotePositionFees>) -> Result<EarnedFees> {
+        ctx.accounts.quote_position_fees()
+    }
+
+    pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+        ctx.accounts.close_pool(&ctx.bumps)
+    }
+
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        ctx.accounts.migrate_pool()
+    }
+
+    pub fn update_lp_metadata(
+        ctx: Context<UpdateLpMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        ctx.accounts
+            .update_lp_metadata(name, symbol, uri, &ctx.bumps)
+    }
+
+    pub fn initialize_farm(ctx: Context<InitializeFarm>) -> Result<()> {
+        ctx.accounts.initialize_farm(&ctx.bumps)
+    }
+
+    pub fn add_farm_reward(ctx: Context<AddFarmReward>) -> Result<()> {
+        ctx.accounts.add_farm_reward()
+    }
+
+    pub fn fund_farm_reward(
+        ctx: Context<FundFarmReward>,
+        reward_index: u8,
+        amount: u64,
+        emissions_per_second: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .fund_farm_reward(reward_index, amount, emissions_per_second)
+    }
+
+    pub fn stake_lp(ctx: Context<StakeLp>, amount: u64) -> Result<()> {
+        ctx.accounts.stake_lp(amount, &ctx.bumps)
+    }
+
+    pub fn unstake_lp(ctx: Context<UnstakeLp>, amount: u64) -> Result<()> {
+        ctx.accounts.unstake_lp(amount)
+    }
+
+    pub fn harvest(ctx: Context<Harvest>, reward_index: u8) -> Result<()> {
+        ctx.accounts.harvest(reward_index)
+    }
+
+    pub fn lock_lp(
+        ctx: Context<LockLp>,
+        lock_id: u64,
+        amount: u64,
+        unlock_timestamp: i64,
+        vesting_duration: u64,
+    ) -> Result<()> {
+        ctx.accounts.lock_lp(
+            lock_id,
+            amount,
+            unlock_timestamp,
+            vesting_duration,
+            &ctx.bumps,
+        )
+    }
+
+    pub fn unlock_lp(ctx: Context<UnlockLp>) -> Result<()> {
+        ctx.accounts.unlock_lp()
+    }
+
+    pub fn set_fee_config(
+        ctx: Context<SetFeeConfig>,
+        min_fee_bps: u16,
+        max_fee_bps: u16,
+        dynamic_fee: bool,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_fee_config(min_fee_bps, max_fee_bps, dynamic_fee)
+    }
+
+    pub fn initialize_multi_pool<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeMultiPool<'info>>,
+        mints: Vec<Pubkey>,
+        fee_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts
+            .initialize_multi_pool(mints, fee_bps, ctx.remaining_accounts, &ctx.bumps)
+    }
+
+    pub fn swap_multi<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapMulti<'info>>,
+        index_in: u8,
+        index_out: u8,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        ctx.accounts.swap_multi(
+            index_in,
+            index_out,
+            amount_in,
+            min_amount_out,
+            ctx.remaining_accounts,
+        )
+    }
+
+    pub fn deposit_multi<'info>(
+        ctx: Context<'_, '_, '_, 'info, DepositMulti<'info>>,
+        max_amounts: Vec<u64>,
+        min_lp_out: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .deposit_multi(max_amounts, min_lp_out, ctx.remaining_accounts)
+    }
+
+    pub fn deposit_multi_single<'info>(
+        ctx: Context<'_, '_, '_, 'info, DepositMultiSingle<'info>>,
+        index: u8,
+        amount_in: u64,
+        min_lp_out: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .deposit_multi_single(index, amount_in, min_lp_out, ctx.remaining_accounts)
+    }
+
+    pub fn withdraw_multi<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawMulti<'info>>,
+        lp_token_quantity: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .withdraw_multi(lp_token_quantity, ctx.remaining_accounts)
+    }
+
+    pub fn withdraw_multi_single<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawMultiSingle<'info>>,
+        index: u8,
+        lp_token_quantity: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        ctx.accounts.withdraw_multi_single(
+            index,
+            lp_token_quantity,
+            min_amount_out,
+            ctx.remaining_accounts,
+        )
+    }
+
+    pub fn initialize_price_feed(
+        ctx: Context<InitializePriceFeed>,
+        feed_id: u64,
+        expo: i32,
+    ) -> Result<()> {
+        ctx.accounts
+            .initialize_price_feed(feed_id, expo, &ctx.bumps)
+    }
+
+    pub fn update_price_feed(ctx: Context<UpdatePriceFeed>, price: u64) -> Result<()> {
+        ctx.accounts.update_price_feed(price)
+    }
+
+    pub fn set_pool_oracle(
+        ctx: Context<SetPoolOracle>,
+        max_deviation_bps: u16,
+        max_oracle_age: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_pool_oracle(max_deviation_bps, max_oracle_age)
+    }
+
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        max_referral_fee_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts
+            .initialize_config(max_referral_fee_bps, &ctx.bumps)
+    }
+
+    pub fn update_config(ctx: Context<UpdateConfig>, max_referral_fee_bps: u16) -> Result<()> {
+        ctx.accounts.update_config(max_referral_fee_bps)
+    }
+
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        ctx.accounts.register_referrer(&ctx.bumps)
+    }
+
+    pub fn remove_referrer(ctx: Context<RemoveReferrer>) -> Result<()> {
+        ctx.accounts.remove_referrer()
+    }
+
+    pub fn set_referral_fee(ctx: Context<SetReferralFee>, referral_fee_bps: u16) -> Result<()> {
+        ctx.accounts.set_referral_fee(referral_fee_bps)
+    }
+
+    pub fn set_allowlist_authority(
+        ctx: Context<SetAllowlistAuthority>,
+        authority: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.set_allowlist_authority(authority)
+    }
+
+    pub fn add_allowlist_entry(ctx: Context<AddAllowlistEntry>) -> Result<()> {
+        ctx.accounts.add_allowlist_entry(&ctx.bumps)
+    }
+
+    pub fn remove_allowlist_entry(ctx: Context<RemoveAllowlistEntry>) -> Result<()> {
+        ctx.accounts.remove_allowlist_entry()
+    }
+
+    pub fn initialize_launch(
+        ctx: Context<InitializeLaunch>,
+        base_amount: u64,
+        virtual_quote_reserve: u64,
+        graduation_quote_reserve: u64,
+        base_is_a: bool,
+    ) -> Result<()> {
+        ctx.accounts.initialize_launch(
+            base_amount,
+            virtual_quote_reserve,
+            graduation_quote_reserve,
+            base_is_a,
+        )
+    }
+
+    pub fn swap_launch(ctx: Context<SwapLaunch>, quantity: u64, is_a: bool) -> Result<()> {
+        ctx.accounts.swap_launch(quantity, is_a, &ctx.bumps)
+    }
+
+    pub fn graduate(ctx: Context<Graduate>) -> Result<()> {
+        ctx.accounts.graduate(&ctx.bumps)
+    }
+
+    pub fn set_circuit_breaker(
+        ctx: Context<SetCircuitBreaker>,
+        window_slots: u64,
+        max_price_change_bps: u16,
+        max_outflow_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_circuit_breaker(window_slots, max_price_change_bps, max_outflow_bps)
+    }
+
+    pub fn place_limit_order(
+        ctx: Context<PlaceLimitOrder>,
+        order_id: u64,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .place_limit_order(order_id, amount_in, min_amount_out, &ctx.bumps)
+    }
+
+    pub fn cancel_limit_order(ctx: Context<CancelLimitOrder>) -> Result<()> {
+        ctx.accounts.cancel_limit_order()
+    }
+
+    pub fn fill_orders<'info>(ctx: Context<'_, '_, '_, 'info, FillOrders<'info>>) -> Result<()> {
+        ctx.accounts.fill_orders(ctx.remaining_accounts, &ctx.bumps)
+    }
+
+    pub fn open_dca(
+        ctx: Context<OpenDca>,
+        dca_id: u64,
+        amount: u64,
+        amount_per_interval: u64,
+        interval: i64,
+        min_out_per_interval: u64,
+    ) -> Result<()> {
+        ctx.accounts.open_dca(
+            dca_id,
+            amount,
+            amount_per_interval,
+            interval,
+            min_out_per_interval,
+            &ctx.bumps,
+        )
+    }
+
+    pub fn execute_dca(ctx: Context<ExecuteDca>) -> Result<()> {
+        ctx.accounts.execute_dca(&ctx.bumps)
+    }
+
+    pub fn close_dca(ctx: Context<CloseDca>) -> Result<()> {
+        ctx.accounts.close_dca()
+    }
+
+    pub fn set_batch_mode(
+        ctx: Context<SetBatchMode>,
+        window_slots: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_batch_mode(window_slots, min_amount_a, min_amount_b)
+    }
+
+    pub fn submit_intent(
+        ctx: Context<SubmitIntent>,
+        start_slot: u64,
+        amount_in: u64,
+        sell_a: bool,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .submit_intent(start_slot, amount_in, sell_a, min_amount_out, &ctx.bumps)
+    }
+
+    pub fn cancel_intent(ctx: Context<CancelIntent>) -> Result<()> {
+        ctx.accounts.cancel_intent()
+    }
+
+    pub fn settle_batch<'info>(ctx: Context<'_, '_, '_, 'info, SettleBatch<'info>>) -> Result<()> {
+        ctx.accounts
+            .settle_batch(ctx.remaining_accounts, &ctx.bumps)
+    }
+
+    pub fn claim_intent(ctx: Context<ClaimIntent>) -> Result<()> {
+        ctx.accounts.claim_intent()
+    }
 }
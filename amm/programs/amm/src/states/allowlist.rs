@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+// Marks a wallet as allowed on a permissioned pool, [b"allowlist", amm, wallet]
+#[derive(InitSpace)]
+#[account]
+pub struct AllowlistEntry {
+    pub amm: Pubkey,
+    pub wallet: Pubkey,
+    pub added_at: i64,
+    pub bump: u8,
+}
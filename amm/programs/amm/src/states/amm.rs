@@ -7,9 +7,66 @@ pub struct AMM {
     pub token_b_mint: Pubkey,
     pub lp_mint: Pubkey,
     pub lp_bump: u8,
-    pub _padding: [u8; 7], // Padding for alignment
+    pub version: u8,       // 0 for pools created before the field existed
+    pub lp_decimals: u8,   // max of the two mints' decimals
+    pub _padding: [u8; 5], // Padding for alignment
     pub reserve_a: Pubkey,
     pub reserve_b: Pubkey,
     pub pool_authority: Pubkey,
     pub lp_supply: u64,
+    pub creator: Pubkey,
+    pub created_slot: u64,
+    pub created_at: i64,
+    // swap fee - fixed at min_fee_bps unless dynamic_fee is set, all zero
+    // on pools created before fees existed
+    pub last_price: u128, // Q64.64 price of A in B seen by the last swap
+    pub volatility: u64,  // price movement in bps, decays over time
+    pub last_swap_ts: i64,
+    pub min_fee_bps: u16,
+    pub max_fee_bps: u16,
+    pub dynamic_fee: u8,
+    pub _fee_padding: [u8; 3],
+    // trades are rejected when they leave the pool price outside the band
+    // around the oracle price - default oracle means unguarded
+    pub oracle: Pubkey,
+    pub max_oracle_age: i64, // seconds
+    pub max_deviation_bps: u16,
+    pub referral_fee_bps: u16, // share of the swap fee paid to referrers
+    pub _padding2: [u8; 4],
+    // when set, only allowlisted wallets (or trades co-signed by this
+    // authority) can swap and provide liquidity
+    pub allowlist_authority: Pubkey,
+    // launch pools, at [b"launch", a, b], price trades off real + virtual
+    // reserves until their real quote reserve reaches graduation_quote_reserve
+    pub virtual_reserve_a: u64,
+    pub virtual_reserve_b: u64,
+    pub graduation_quote_reserve: u64,
+    pub launch_state: u8, // LAUNCH_NONE, LAUNCH_ACTIVE or LAUNCH_GRADUATED
+    pub _padding3: [u8; 7],
+    // circuit breaker - swaps are rejected once the price has moved more than
+    // max_price_change_bps, or more than max_outflow_bps of a reserve has been
+    // paid out, since the start of the current window
+    pub breaker_window_slots: u64, // 0 disables the breaker
+    pub window_start_price: u128,  // Q64.64, like last_price
+    pub window_start_slot: u64,
+    pub window_start_reserve_a: u64,
+    pub window_start_reserve_b: u64,
+    pub window_outflow_a: u64,
+    pub window_outflow_b: u64,
+    pub max_price_change_bps: u16, // 0 means no price limit
+    pub max_outflow_bps: u16,      // 0 means no outflow limit
+    pub _padding4: [u8; 4],
+    // batch mode - when set, trades are only executed as swap intents
+    // settled once per window of batch_window_slots at a single price
+    pub batch_window_slots: u64, // 0 means trades execute immediately
+    pub _padding5: [u8; 8],
+    // swap fees left in the reserves per LP token, Q64.64 and wrapping - only
+    // differences between two readings are meaningful
+    pub fee_growth_a: u128,
+    pub fee_growth_b: u128,
+    // smallest amount_in of an intent selling A (or B), so batch windows
+    // can't be filled up with dust
+    pub batch_min_amount_a: u64,
+    pub batch_min_amount_b: u64,
+    pub _reserved: [u8; 224], // Space for new fields without resizing the account
 }
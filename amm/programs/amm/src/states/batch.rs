@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+// Collects swap intents for one window of a pool in batch mode,
+// [b"batch", amm, start_slot]. The vaults escrow the inputs and then hold the
+// outputs until every intent has been claimed, after which the batch and its
+// vaults are closed back to the payer.
+// Settlement runs in passes over pages of intents: each pass checks every
+// intent still in against one clearing price, and the trade executes after a
+// pass that excludes nobody.
+#[derive(InitSpace)]
+#[account]
+pub struct Batch {
+    pub amm: Pubkey,
+    pub payer: Pubkey, // created the batch and its vaults, gets the rent back
+    pub start_slot: u64,
+    pub end_slot: u64, // intents are accepted (and cancelled) until here, settlement after
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub total_in_a: u64,
+    pub total_in_b: u64,
+    pub total_out_a: u64, // paid pro rata to the intents selling B - the pass price until settled
+    pub total_out_b: u64, // paid pro rata to the intents selling A
+    pub paid_out_a: u64,
+    pub paid_out_b: u64,
+    pub intent_count: u16, // not yet cancelled or claimed
+    pub sell_a_count: u16, // not yet cancelled, excluded or claimed
+    pub sell_b_count: u16,
+    pub excluded_count: u16,
+    pub fee_bps: u16,   // fixed when settlement starts
+    pub pass: u32,      // 0 until settlement starts
+    pub pass_in_a: u64, // totals the pass price was computed for
+    pub pass_in_b: u64,
+    pub pass_intents: u16,  // intents still in when the pass started
+    pub checked_count: u16, // of those, checked against the pass price so far
+    pub excluded_in_pass: u16,
+    pub settled: bool,
+    pub bump: u8,
+}
+
+// One per wallet and batch, [b"swap_intent", batch, owner]
+#[derive(InitSpace)]
+#[account]
+pub struct SwapIntent {
+    pub batch: Pubkey,
+    pub owner: Pubkey,
+    pub sell_a: bool,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub excluded: bool, // set at settlement when min_amount_out can't be met - refunded on claim
+    pub checked_pass: u32, // last settlement pass that checked it
+    pub bump: u8,
+}
@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+// Program-wide settings, a single PDA at [b"config"]
+#[derive(InitSpace)]
+#[account]
+pub struct Config {
+    pub authority: Pubkey,
+    pub max_referral_fee_bps: u16, // cap on the share of a swap fee paid to referrers
+    pub bump: u8,
+}
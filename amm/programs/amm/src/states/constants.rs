@@ -0,0 +1,33 @@
+// Layout version written to `AMM.version` by initialize and migrate_pool
+pub const AMM_VERSION: u8 = 2;
+
+// Farms pay out up to 3 reward mints at once
+pub const FARM_MAX_REWARDS: usize = 3;
+// Scaling for the per-LP-token reward accumulators
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+// Swap fees can never exceed 10%
+pub const MAX_FEE_BPS: u16 = 1_000;
+// The volatility accumulator halves every 60 seconds without swaps
+pub const VOLATILITY_HALF_LIFE: i64 = 60;
+// Each 10 bps of accumulated volatility adds 1 bp of fee
+pub const VOLATILITY_FEE_DIVISOR: u64 = 10;
+
+// Multi-asset pools hold between 2 and 8 tokens
+pub const MULTI_POOL_MIN_TOKENS: usize = 2;
+pub const MULTI_POOL_MAX_TOKENS: usize = 8;
+
+// AMM.launch_state - a pair's standard pool is LAUNCH_ACTIVE while its launch
+// pool trades against virtual reserves, and LAUNCH_GRADUATED once the launch
+// reserves have moved into it
+pub const LAUNCH_NONE: u8 = 0;
+pub const LAUNCH_ACTIVE: u8 = 1;
+pub const LAUNCH_GRADUATED: u8 = 2;
+
+// Keepers filling limit orders and DCA tranches earn 0.1% of the output
+pub const KEEPER_BOUNTY_BPS: u64 = 10;
+
+// settle_batch takes a batch's intents over as many transactions as needed,
+// this only bounds how long a batch can take to settle and claim
+pub const BATCH_MAX_INTENTS: u16 = 1024;
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+// `amm` comes first so a pool's DCA orders can be listed with a memcmp filter
+// at offset 8 (right after the discriminator)
+#[derive(InitSpace)]
+#[account]
+pub struct DcaOrder {
+    pub amm: Pubkey,
+    pub owner: Pubkey,
+    pub input_vault: Pubkey,  // tokens still to be sold
+    pub output_vault: Pubkey, // proceeds, released on close
+    pub dca_id: u64,
+    pub sell_a: bool,
+    pub amount_per_interval: u64,
+    pub interval: i64,             // seconds between tranches
+    pub min_out_per_interval: u64, // min price = min_out_per_interval / amount_per_interval
+    pub next_execution_ts: i64,
+    pub total_in: u64,
+    pub total_out: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
@@ -10,4 +10,88 @@ pub enum AMMError {
     InvalidQuantity,
     #[msg("Invalid Liquidity specified")]
     InvalidLiquidity,
+    #[msg("AMM Pool has no liquidity")]
+    EmptyPool,
+    #[msg("Output is below the minimum amount specified")]
+    SlippageExceeded,
+    #[msg("AMM Pool still has liquidity")]
+    PoolNotEmpty,
+    #[msg("Signer is not authorized for this AMM Pool")]
+    Unauthorized,
+    #[msg("AMM Pool is already on the latest version")]
+    AlreadyMigrated,
+    #[msg("AMM Pool does not contain wrapped SOL")]
+    NotNativeMint,
+    #[msg("Farm already has the maximum number of rewards")]
+    FarmFull,
+    #[msg("Invalid farm reward")]
+    InvalidReward,
+    #[msg("Not enough LP tokens staked")]
+    InsufficientStake,
+    #[msg("Unlock time must be in the future")]
+    InvalidUnlockTime,
+    #[msg("LP tokens are still locked")]
+    LpLocked,
+    #[msg("Invalid fee configuration")]
+    InvalidFee,
+    #[msg("Multi-asset pools hold 2 to 8 tokens")]
+    InvalidTokenCount,
+    #[msg("Mints must be passed in ascending order without duplicates")]
+    UnsortedMints,
+    #[msg("Accounts do not match the multi-asset pool")]
+    InvalidPoolAccounts,
+    #[msg("Oracle account does not match the pool")]
+    InvalidOracle,
+    #[msg("Oracle price is stale")]
+    OracleStale,
+    #[msg("Pool price deviates too far from the oracle price")]
+    PriceDeviationExceeded,
+    #[msg("Referral fee exceeds the configured maximum")]
+    ReferralFeeTooHigh,
+    #[msg("Referrer token account must hold the input token")]
+    InvalidReferrer,
+    #[msg("Signer is not on the pool allowlist")]
+    NotAllowlisted,
+    #[msg("Invalid launch configuration")]
+    InvalidLaunch,
+    #[msg("AMM Pool is still in its launch phase")]
+    LaunchActive,
+    #[msg("Quote reserve has not reached the graduation threshold")]
+    GraduationThresholdNotReached,
+    #[msg("Not enough real reserves for this trade")]
+    InsufficientReserves,
+    #[msg("Invalid circuit breaker configuration")]
+    InvalidCircuitBreaker,
+    #[msg("Circuit breaker tripped - try again once the window rolls over")]
+    CircuitBreakerTripped,
+    #[msg("Invalid limit order")]
+    InvalidOrder,
+    #[msg("None of the orders can be filled at the current price")]
+    NoEligibleOrders,
+    #[msg("DCA tranche is not due yet")]
+    DcaNotDue,
+    #[msg("AMM Pool only trades through batch auctions")]
+    BatchModeActive,
+    #[msg("AMM Pool is not in batch mode")]
+    BatchModeInactive,
+    #[msg("Batch is not accepting intents")]
+    BatchClosed,
+    #[msg("Batch window has not ended yet")]
+    BatchNotReady,
+    #[msg("Batch is already settled")]
+    BatchSettled,
+    #[msg("Batch has the maximum number of intents")]
+    BatchFull,
+    #[msg("Intents do not match the batch")]
+    InvalidIntent,
+    #[msg("Signer does not hold the position NFT")]
+    NotPositionHolder,
+    #[msg("Position does not hold enough shares")]
+    InsufficientShares,
+    #[msg("Unwrapping SOL requires the temporary wSOL account")]
+    MissingTempWsolAccount,
+    #[msg("Swappers cannot refer themselves")]
+    SelfReferral,
+    #[msg("Intent is below the pool's minimum size")]
+    IntentTooSmall,
 }
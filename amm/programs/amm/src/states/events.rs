@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct SwapReferral {
+    pub amm: Pubkey,
+    pub referrer_token_account: Pubkey,
+    pub mint: Pubkey,
+    pub swap_fee: u64,
+    pub referral_amount: u64,
+}
+
+#[event]
+pub struct AllowlistEntryAdded {
+    pub amm: Pubkey,
+    pub wallet: Pubkey,
+}
+
+#[event]
+pub struct AllowlistEntryRemoved {
+    pub amm: Pubkey,
+    pub wallet: Pubkey,
+}
+
+#[event]
+pub struct LaunchGraduated {
+    pub amm: Pubkey,
+    pub quote_reserve: u64,
+    pub base_burned: u64,
+    pub lp_locked: u64,
+}
+
+#[event]
+pub struct LimitOrderFilled {
+    pub amm: Pubkey,
+    pub order: Pubkey,
+    pub owner: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub keeper_bounty: u64,
+}
+
+#[event]
+pub struct DcaExecuted {
+    pub amm: Pubkey,
+    pub dca: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub keeper_tip: u64,
+}
+
+#[event]
+pub struct BatchSettled {
+    pub amm: Pubkey,
+    pub batch: Pubkey,
+    pub total_in_a: u64,
+    pub total_in_b: u64,
+    pub total_out_a: u64,
+    pub total_out_b: u64,
+    pub excluded_intents: u16,
+}
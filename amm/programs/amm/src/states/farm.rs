@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::states::FARM_MAX_REWARDS;
+
+#[derive(InitSpace)]
+#[account]
+pub struct Farm {
+    pub amm: Pubkey,
+    pub lp_mint: Pubkey,
+    pub lp_vault: Pubkey,
+    pub authority: Pubkey, // pool creator - adds and funds rewards
+    pub total_staked: u64,
+    pub reward_count: u8,
+    pub rewards: [FarmReward; FARM_MAX_REWARDS],
+    pub bump: u8,
+}
+
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct FarmReward {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub emissions_per_second: u64,
+    pub reward_per_share: u128, // scaled by REWARD_PRECISION
+    pub last_update_ts: i64,
+    pub end_ts: i64, // emissions stop once the funded amount is used up
+}
+
+#[derive(InitSpace)]
+#[account]
+pub struct FarmPosition {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub reward_debt: [u128; FARM_MAX_REWARDS],
+    pub rewards_owed: [u64; FARM_MAX_REWARDS],
+    pub bump: u8,
+}
@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+// `amm` comes first so a pool's order book can be listed with a memcmp filter
+// at offset 8 (right after the discriminator)
+#[derive(InitSpace)]
+#[account]
+pub struct LimitOrder {
+    pub amm: Pubkey,
+    pub owner: Pubkey,
+    pub vault: Pubkey, // escrows amount_in of the input token
+    pub order_id: u64,
+    pub sell_a: bool,
+    pub amount_in: u64,
+    pub min_amount_out: u64, // limit price = min_amount_out / amount_in
+    pub created_at: i64,
+    pub bump: u8,
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+// Opt-in record of the swap fees a wallet's LP tokens earned, [b"lp_fees", amm,
+// owner], created with `create_lp_fee_checkpoint`. It counts the LP tokens
+// minted to the owner through deposits made with it, less those burned through
+// withdrawals, capped at the wallet's balance. LP tokens transferred in never
+// count, and tokens transferred out stop counting from the next settlement or
+// quote.
+#[derive(InitSpace)]
+#[account]
+pub struct LpFeeCheckpoint {
+    pub amm: Pubkey,
+    pub owner: Pubkey,
+    // AMM.fee_growth_a/b at the last checkpoint
+    pub fee_growth_a: u128,
+    pub fee_growth_b: u128,
+    // LP tokens the checkpoint earns fees on
+    pub lp_balance: u64,
+    // fees earned up to the last checkpoint
+    pub earned_a: u64,
+    pub earned_b: u64,
+    pub bump: u8,
+}
+
+// Return data of `quote_earned_fees` and `quote_position_fees`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct EarnedFees {
+    pub fees_a: u64,
+    pub fees_b: u64,
+}
@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+// `amm` comes first so a pool's locks can be listed with a memcmp filter at
+// offset 8 (right after the discriminator)
+#[derive(InitSpace)]
+#[account]
+pub struct LpLock {
+    pub amm: Pubkey,
+    pub lp_mint: Pubkey,
+    pub locker: Pubkey,
+    pub beneficiary: Pubkey,
+    pub lock_id: u64,
+    pub amount: u64,
+    pub withdrawn: u64,
+    pub locked_at: i64,
+    pub unlock_ts: i64,
+    pub vesting_end_ts: i64, // equal to unlock_ts when the lock does not vest
+    pub bump: u8,
+}
@@ -1,5 +1,47 @@
+pub mod allowlist;
+pub use allowlist::*;
+
 pub mod amm;
 pub use amm::*;
 
+pub mod batch;
+pub use batch::*;
+
+pub mod config;
+pub use config::*;
+
+pub mod constants;
+pub use constants::*;
+
+pub mod dca;
+pub use dca::*;
+
 pub mod error;
 pub use error::*;
+
+pub mod events;
+pub use events::*;
+
+pub mod farm;
+pub use farm::*;
+
+pub mod limit_order;
+pub use limit_order::*;
+
+pub mod lp_fees;
+pub use lp_fees::*;
+
+pub mod lp_lock;
+pub use lp_lock::*;
+
+pub mod multi_pool;
+pub use multi_pool::*;
+
+pub mod position;
+pub use position::*;
+
+pub mod price_feed;
+pub use price_feed::*;
+
+pub mod referrer;
+pub use referrer::*;
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+use crate::states::MULTI_POOL_MAX_TOKENS;
+
+// Equal-weight pool over 2-8 tokens. Only the first `token_count` entries of
+// the fixed-size arrays are used, ordered by mint.
+#[derive(InitSpace)]
+#[account]
+pub struct MultiPool {
+    pub mints_hash: [u8; 32], // PDA seed - hash of the sorted mints
+    pub creator: Pubkey,
+    pub lp_mint: Pubkey,
+    pub lp_supply: u64,
+    pub lp_decimals: u8, // max of the mints' decimals
+    pub fee_bps: u16,
+    pub token_count: u8,
+    pub mints: [Pubkey; MULTI_POOL_MAX_TOKENS],
+    pub reserves: [Pubkey; MULTI_POOL_MAX_TOKENS],
+    pub decimals: [u8; MULTI_POOL_MAX_TOKENS],
+    pub bump: u8,
+    pub lp_mint_bump: u8,
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+// An LP position held as a 1-of-1 NFT instead of fungible LP tokens,
+// [b"position", position_mint]. Its shares count towards lp_supply like LP
+// tokens do, and whoever holds the NFT can withdraw them.
+#[derive(InitSpace)]
+#[account]
+pub struct Position {
+    pub amm: Pubkey,
+    pub position_mint: Pubkey,
+    pub shares: u64,
+    // cost basis and what has been taken out, for per-position PnL
+    pub deposited_a: u64,
+    pub deposited_b: u64,
+    pub withdrawn_a: u64,
+    pub withdrawn_b: u64,
+    pub entry_price: u128, // Q64.64 price of A in B at deposit
+    // swap fees earned, brought up to date whenever the position is touched
+    pub fees_accrued_a: u64,
+    pub fees_accrued_b: u64,
+    // AMM.fee_growth_a/b when fees_accrued_a/b were last brought up to date
+    pub fee_growth_a: u128,
+    pub fee_growth_b: u128,
+    pub created_at: i64,
+    pub bump: u8,
+}
@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+// Minimal price feed format pools can be guarded by. Prices are for one whole
+// token A in whole token B: price X 10^expo.
+#[derive(InitSpace)]
+#[account]
+pub struct PriceFeed {
+    pub authority: Pubkey,
+    pub feed_id: u64,
+    pub price: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+    pub bump: u8,
+}
@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+// A wallet the config authority allows to earn referral fees, [b"referrer", owner]
+#[derive(InitSpace)]
+#[account]
+pub struct Referrer {
+    pub owner: Pubkey,
+    pub registered_at: i64,
+    pub bump: u8,
+}
@@ -0,0 +1,100 @@
+use amm::{cpi_helpers::PoolAccounts, program::Amm};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token},
+};
+
+use crate::error::RouterError;
+
+// The pool accounts are passed straight through - the AMM checks them, and
+// creates the LP token account and fee checkpoint on the first deposit
+
+#[derive(Accounts)]
+pub struct DepositWithMinLp<'info> {
+    pub amm_program: Program<'info, Amm>,
+
+    /// CHECK: checked by the AMM
+    #[account(mut)]
+    pub amm: UncheckedAccount<'info>,
+
+    /// CHECK: checked by the AMM
+    #[account(mut)]
+    pub token_a_account: UncheckedAccount<'info>,
+    /// CHECK: checked by the AMM
+    #[account(mut)]
+    pub token_b_account: UncheckedAccount<'info>,
+    /// CHECK: checked by the AMM
+    #[account(mut)]
+    pub token_lp_account: UncheckedAccount<'info>,
+    /// CHECK: checked by the AMM, only passed by wallets tracking their fees
+    #[account(mut)]
+    pub lp_fee_checkpoint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: checked by the AMM
+    #[account(mut)]
+    pub reserve_a: UncheckedAccount<'info>,
+    /// CHECK: checked by the AMM
+    #[account(mut)]
+    pub reserve_b: UncheckedAccount<'info>,
+    /// CHECK: checked by the AMM
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+    // the LP tokens minted are read off its supply
+    #[account(mut)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> DepositWithMinLp<'info> {
+    pub fn deposit_with_min_lp(
+        &mut self,
+        quantity_a: u64,
+        quantity_b: u64,
+        min_lp_tokens: u64,
+    ) -> Result<u64> {
+        let supply_before = self.lp_mint.supply;
+
+        let pool = PoolAccounts {
+            amm: self.amm.to_account_info(),
+            reserve_a: self.reserve_a.to_account_info(),
+            reserve_b: self.reserve_b.to_account_info(),
+            pool_authority: self.pool_authority.to_account_info(),
+            token_a_mint: self.token_a_mint.to_account_info(),
+            token_b_mint: self.token_b_mint.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            associated_token_program: self.associated_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+
+        let deposit_ctx = CpiContext::new(
+            self.amm_program.to_account_info(),
+            pool.deposit(
+                self.token_a_account.to_account_info(),
+                self.token_b_account.to_account_info(),
+                self.token_lp_account.to_account_info(),
+                self.lp_fee_checkpoint
+                    .as_ref()
+                    .map(|checkpoint| checkpoint.to_account_info()),
+                self.lp_mint.to_account_info(),
+                self.signer.to_account_info(),
+            ),
+        );
+
+        amm::cpi::deposit(deposit_ctx, quantity_a, quantity_b)?;
+
+        self.lp_mint.reload()?;
+        let lp_tokens = self.lp_mint.supply - supply_before;
+        require!(lp_tokens >= min_lp_tokens, RouterError::SlippageExceeded);
+
+        Ok(lp_tokens)
+    }
+}
@@ -0,0 +1,7 @@
+pub mod deposit_with_min_lp;
+pub mod swap_with_min_out;
+pub mod withdraw_with_min_out;
+
+pub use deposit_with_min_lp::*;
+pub use swap_with_min_out::*;
+pub use withdraw_with_min_out::*;
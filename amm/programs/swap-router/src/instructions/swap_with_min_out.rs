@@ -0,0 +1,102 @@
+use amm::{cpi_helpers::PoolAccounts, program::Amm};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+use crate::error::RouterError;
+
+// The pool accounts are passed straight through - the AMM checks them
+
+#[derive(Accounts)]
+pub struct SwapWithMinOut<'info> {
+    pub amm_program: Program<'info, Amm>,
+
+    /// CHECK: checked by the AMM
+    #[account(mut)]
+    pub amm: UncheckedAccount<'info>,
+
+    // the AMM pays out to the signer's associated token accounts
+    #[account(
+        mut,
+        associated_token::mint = token_a_mint,
+        associated_token::authority = signer
+    )]
+    pub token_a_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = token_b_mint,
+        associated_token::authority = signer
+    )]
+    pub token_b_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: checked by the AMM
+    #[account(mut)]
+    pub reserve_a: UncheckedAccount<'info>,
+    /// CHECK: checked by the AMM
+    #[account(mut)]
+    pub reserve_b: UncheckedAccount<'info>,
+    /// CHECK: checked by the AMM
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> SwapWithMinOut<'info> {
+    pub fn swap_with_min_out(
+        &mut self,
+        quantity: u64,
+        is_a: bool,
+        min_amount_out: u64,
+    ) -> Result<u64> {
+        let balance_before = if is_a {
+            self.token_b_account.amount
+        } else {
+            self.token_a_account.amount
+        };
+
+        let pool = PoolAccounts {
+            amm: self.amm.to_account_info(),
+            reserve_a: self.reserve_a.to_account_info(),
+            reserve_b: self.reserve_b.to_account_info(),
+            pool_authority: self.pool_authority.to_account_info(),
+            token_a_mint: self.token_a_mint.to_account_info(),
+            token_b_mint: self.token_b_mint.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            associated_token_program: self.associated_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+
+        let swap_ctx = CpiContext::new(
+            self.amm_program.to_account_info(),
+            pool.swap(
+                self.token_a_account.to_account_info(),
+                self.token_b_account.to_account_info(),
+                self.signer.to_account_info(),
+            ),
+        );
+
+        amm::cpi::swap(swap_ctx, quantity, is_a)?;
+
+        let user_out_account = if is_a {
+            &mut self.token_b_account
+        } else {
+            &mut self.token_a_account
+        };
+        user_out_account.reload()?;
+
+        let amount_out = user_out_account.amount - balance_before;
+        require!(amount_out >= min_amount_out, RouterError::SlippageExceeded);
+
+        Ok(amount_out)
+    }
+}
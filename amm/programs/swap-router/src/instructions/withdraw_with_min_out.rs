@@ -0,0 +1,111 @@
+use amm::{cpi_helpers::PoolAccounts, program::Amm};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+use crate::error::RouterError;
+
+// The pool accounts are passed straight through - the AMM checks them
+
+#[derive(Accounts)]
+pub struct WithdrawWithMinOut<'info> {
+    pub amm_program: Program<'info, Amm>,
+
+    /// CHECK: checked by the AMM
+    #[account(mut)]
+    pub amm: UncheckedAccount<'info>,
+
+    // the AMM pays out to the signer's associated token accounts
+    #[account(
+        mut,
+        associated_token::mint = token_a_mint,
+        associated_token::authority = signer
+    )]
+    pub token_a_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = token_b_mint,
+        associated_token::authority = signer
+    )]
+    pub token_b_account: Box<Account<'info, TokenAccount>>,
+    /// CHECK: checked by the AMM
+    #[account(mut)]
+    pub token_lp_account: UncheckedAccount<'info>,
+    /// CHECK: checked by the AMM, only passed by wallets tracking their fees
+    #[account(mut)]
+    pub lp_fee_checkpoint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: checked by the AMM
+    #[account(mut)]
+    pub reserve_a: UncheckedAccount<'info>,
+    /// CHECK: checked by the AMM
+    #[account(mut)]
+    pub reserve_b: UncheckedAccount<'info>,
+    /// CHECK: checked by the AMM
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    pub token_b_mint: Box<Account<'info, Mint>>,
+    /// CHECK: checked by the AMM
+    #[account(mut)]
+    pub lp_mint: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+impl<'info> WithdrawWithMinOut<'info> {
+    pub fn withdraw_with_min_out(
+        &mut self,
+        lp_token_quantity: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    ) -> Result<()> {
+        let balance_a_before = self.token_a_account.amount;
+        let balance_b_before = self.token_b_account.amount;
+
+        let pool = PoolAccounts {
+            amm: self.amm.to_account_info(),
+            reserve_a: self.reserve_a.to_account_info(),
+            reserve_b: self.reserve_b.to_account_info(),
+            pool_authority: self.pool_authority.to_account_info(),
+            token_a_mint: self.token_a_mint.to_account_info(),
+            token_b_mint: self.token_b_mint.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            associated_token_program: self.associated_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+
+        let withdraw_ctx = CpiContext::new(
+            self.amm_program.to_account_info(),
+            pool.withdraw(
+                self.token_a_account.to_account_info(),
+                self.token_b_account.to_account_info(),
+                self.token_lp_account.to_account_info(),
+                self.lp_fee_checkpoint
+                    .as_ref()
+                    .map(|checkpoint| checkpoint.to_account_info()),
+                self.lp_mint.to_account_info(),
+                self.signer.to_account_info(),
+            ),
+        );
+
+        amm::cpi::withdraw(withdraw_ctx, lp_token_quantity)?;
+
+        self.token_a_account.reload()?;
+        self.token_b_account.reload()?;
+        require!(
+            self.token_a_account.amount - balance_a_before >= min_amount_a
+                && self.token_b_account.amount - balance_b_before >= min_amount_b,
+            RouterError::SlippageExceeded
+        );
+
+        Ok(())
+    }
+}
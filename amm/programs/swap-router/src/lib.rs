@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+declare_id!("7MNtpxY9fWtqAwZVdVcNPdBhrodUDqJNHKKtJCsXT21p");
+
+mod error;
+mod instructions;
+
+use crate::instructions::*;
+
+// Example integrator - swaps, deposits and withdraws through the AMM with CPI,
+// using the helpers in `amm::cpi_helpers`, and adds the minimum output checks
+// the AMM leaves to the client.
+
+#[program]
+pub mod swap_router {
+    use super::*;
+
+    pub fn swap_with_min_out(
+        ctx: Context<SwapWithMinOut>,
+        quantity: u64,
+        is_a: bool,
+        min_amount_out: u64,
+    ) -> Result<u64> {
+        ctx.accounts
+            .swap_with_min_out(quantity, is_a, min_amount_out)
+    }
+
+    pub fn deposit_with_min_lp(
+        ctx: Context<DepositWithMinLp>,
+        quantity_a: u64,
+        quantity_b: u64,
+        min_lp_tokens: u64,
+    ) -> Result<u64> {
+        ctx.accounts
+            .deposit_with_min_lp(quantity_a, quantity_b, min_lp_tokens)
+    }
+
+    pub fn withdraw_with_min_out(
+        ctx: Context<WithdrawWithMinOut>,
+        lp_token_quantity: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .withdraw_with_min_out(lp_token_quantity, min_amount_a, min_amount_b)
+    }
+}
@@ -1,16 +1,2364 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Amm } from "../target/types/amm";
+import { SwapRouter } from "../target/types/swap_router";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SYSVAR_RENT_PUBKEY,
+  SystemProgram,
+  Transaction,
+} from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  NATIVE_MINT,
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccount,
+  createMint,
+  createWrappedNativeAccount,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getMint,
+  mintTo,
+  transfer,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { createHash } from "crypto";
+
+const U64_MAX = (1n << 64n) - 1n;
+const BPF_LOADER_UPGRADEABLE_PROGRAM_ID = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
+  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+);
 
 describe("amm", () => {
   // Configure the client to use the local cluster.
-  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const connection = provider.connection;
+  const payer = (provider.wallet as anchor.Wallet).payer;
 
   const program = anchor.workspace.Amm as Program<Amm>;
 
-  it("Is initialized!", async () => {
-    // Add your test here.
-    const tx = await program.methods.initialize().rpc();
-    console.log("Your transaction signature", tx);
+  const programData = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE_PROGRAM_ID
+  )[0];
+  const config = PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    program.programId
+  )[0];
+
+  // 1. Utils
+  const bn = (value: bigint) => new anchor.BN(value.toString());
+
+  const balance = async (address: PublicKey): Promise<bigint> =>
+    (await getAccount(connection, address)).amount;
+
+  const pda = (prefix: string, mintA: PublicKey, mintB: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from(prefix), mintA.toBuffer(), mintB.toBuffer()],
+      program.programId
+    )[0];
+
+  const lpFeeCheckpoint = (amm: PublicKey, owner: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_fees"), amm.toBuffer(), owner.toBuffer()],
+      program.programId
+    )[0];
+
+  // Pool PDAs and the provider wallet's token accounts for a pair
+  const poolAccounts = (mintA: PublicKey, mintB: PublicKey) => {
+    const amm = pda("amm", mintA, mintB);
+    const lpMint = pda("lp_mint", mintA, mintB);
+
+    return {
+      amm,
+      reserveA: pda("reserve_a", mintA, mintB),
+      reserveB: pda("reserve_b", mintA, mintB),
+      poolAuthority: pda("authority", mintA, mintB),
+      lpMint,
+      tokenAMint: mintA,
+      tokenBMint: mintB,
+      tokenAAccount: getAssociatedTokenAddressSync(mintA, payer.publicKey),
+      tokenBAccount: getAssociatedTokenAddressSync(mintB, payer.publicKey),
+      tokenLpAccount: getAssociatedTokenAddressSync(lpMint, payer.publicKey),
+      // fee tracking is opt-in, see withFeeCheckpoint
+      lpFeeCheckpoint: null as PublicKey | null,
+      lpMetadata: PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("metadata"),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          lpMint.toBuffer(),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      )[0],
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      metadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      rent: SYSVAR_RENT_PUBKEY,
+      signer: payer.publicKey,
+    };
+  };
+
+  type Pool = ReturnType<typeof poolAccounts>;
+
+  // The test mints have no token metadata, so the LP token is named after
+  // their addresses
+  const initialize = (pool: Pool) =>
+    program.methods
+      .initialize()
+      .accounts({ ...pool, tokenAMetadata: null, tokenBMetadata: null })
+      .rpc();
+
+  // Creates two fresh mints, funds the provider wallet with `supply` of each
+  // and initializes a pool for the pair
+  const createPool = async (
+    decimals: number,
+    supply: bigint,
+    decimalsB = decimals
+  ) => {
+    const [mintA, mintB] = await Promise.all(
+      [decimals, decimalsB].map((d) =>
+        createMint(connection, payer, payer.publicKey, null, d)
+      )
+    );
+    const accounts = poolAccounts(mintA, mintB);
+
+    for (const [mint, ata] of [
+      [mintA, accounts.tokenAAccount],
+      [mintB, accounts.tokenBAccount],
+    ]) {
+      await createAssociatedTokenAccount(
+        connection,
+        payer,
+        mint,
+        payer.publicKey
+      );
+      await mintTo(connection, payer, mint, ata, payer, supply);
+    }
+
+    await initialize(accounts);
+
+    return accounts;
+  };
+
+  const deposit = (pool: Pool, quantityA: bigint, quantityB: bigint) =>
+    program.methods
+      .deposit(bn(quantityA), bn(quantityB))
+      .accounts(pool)
+      .rpc();
+
+  const depositSingle = (
+    pool: Pool,
+    amountIn: bigint,
+    isA: boolean,
+    minLpOut: bigint
+  ) =>
+    program.methods
+      .depositSingle(bn(amountIn), isA, bn(minLpOut))
+      .accounts(pool)
+      .rpc();
+
+  const swap = (pool: Pool, quantity: bigint, isA: boolean) =>
+    program.methods.swap(bn(quantity), isA).accounts(pool).rpc();
+
+  const withdraw = (pool: Pool, lpQuantity: bigint) =>
+    program.methods.withdraw(bn(lpQuantity)).accounts(pool).rpc();
+
+  const withdrawSingle = (
+    pool: Pool,
+    lpQuantity: bigint,
+    isA: boolean,
+    minAmountOut: bigint
+  ) =>
+    program.methods
+      .withdrawSingle(bn(lpQuantity), isA, bn(minAmountOut))
+      .accounts(pool)
+      .rpc();
+
+  // Creates the signer's LP fee checkpoint and returns the accounts with it
+  const withFeeCheckpoint = async (pool: Pool, owner = payer) => {
+    const checkpoint = lpFeeCheckpoint(pool.amm, owner.publicKey);
+    await program.methods
+      .createLpFeeCheckpoint()
+      .accounts({
+        ...pool,
+        lpFeeCheckpoint: checkpoint,
+        owner: owner.publicKey,
+      })
+      .signers(owner === payer ? [] : [owner])
+      .rpc();
+    return { ...pool, lpFeeCheckpoint: checkpoint };
+  };
+
+  const closePool = (pool: Pool) =>
+    program.methods
+      .closePool()
+      .accounts({
+        ...pool,
+        creatorTokenAAccount: pool.tokenAAccount,
+        creatorTokenBAccount: pool.tokenBAccount,
+        creator: payer.publicKey,
+      })
+      .rpc();
+
+  const migratePool = (pool: Pool) =>
+    program.methods
+      .migratePool()
+      .accounts({
+        ...pool,
+        program: program.programId,
+        programData,
+      })
+      .rpc();
+
+  // Farm PDAs for a pool and one reward mint
+  const farmAccounts = (pool: Pool, rewardMint: PublicKey) => {
+    const farm = PublicKey.findProgramAddressSync(
+      [Buffer.from("farm"), pool.amm.toBuffer()],
+      program.programId
+    )[0];
+    const seed = (prefix: string, ...keys: PublicKey[]) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from(prefix),
+          farm.toBuffer(),
+          ...keys.map((key) => key.toBuffer()),
+        ],
+        program.programId
+      )[0];
+
+    return {
+      ...pool,
+      farm,
+      lpVault: seed("farm_lp_vault"),
+      position: seed("farm_position", payer.publicKey),
+      rewardMint,
+      rewardVault: seed("farm_reward_vault", rewardMint),
+      rewardTokenAccount: getAssociatedTokenAddressSync(
+        rewardMint,
+        payer.publicKey
+      ),
+      creator: payer.publicKey,
+      authority: payer.publicKey,
+    };
+  };
+
+  // LP lock PDAs for a pool, owned by the provider wallet
+  const lockAccounts = (pool: Pool, lockId: bigint) => {
+    const lock = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("lp_lock"),
+        pool.amm.toBuffer(),
+        payer.publicKey.toBuffer(),
+        bn(lockId).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+    return {
+      ...pool,
+      lock,
+      lockVault: PublicKey.findProgramAddressSync(
+        [Buffer.from("lp_lock_vault"), lock.toBuffer()],
+        program.programId
+      )[0],
+      beneficiary: payer.publicKey,
+      beneficiaryLpAccount: pool.tokenLpAccount,
+    };
+  };
+
+  const chainTime = async () =>
+    BigInt(await connection.getBlockTime(await connection.getSlot()));
+
+  const expectError = async (promise: Promise<string>, error: string) => {
+    try {
+      await promise;
+      assert.fail(`expected ${error}`);
+    } catch (err) {
+      assert.include(String(err), error);
+    }
+  };
+
+  const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
+
+  const setFeeConfig = (
+    pool: Pool,
+    minFeeBps: number,
+    maxFeeBps: number,
+    dynamicFee: boolean
+  ) =>
+    program.methods
+      .setFeeConfig(minFeeBps, maxFeeBps, dynamicFee)
+      .accounts({ ...pool, creator: payer.publicKey })
+      .rpc();
+
+  // Creates a price feed owned by the provider wallet and publishes `price`
+  const createPriceFeed = async (
+    feedId: bigint,
+    price: bigint,
+    expo: number
+  ) => {
+    const priceFeed = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("price_feed"),
+        payer.publicKey.toBuffer(),
+        bn(feedId).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+    const accounts = { priceFeed, authority: payer.publicKey };
+
+    await program.methods
+      .initializePriceFeed(bn(feedId), expo)
+      .accounts(accounts)
+      .rpc();
+    await program.methods.updatePriceFeed(bn(price)).accounts(accounts).rpc();
+
+    return priceFeed;
+  };
+
+  const meta = (pubkey: PublicKey, isWritable: boolean) => ({
+    pubkey,
+    isWritable,
+    isSigner: false,
+  });
+
+  // Creates `count` mints sorted by address, funds the provider wallet and
+  // initializes a multi-asset pool over them
+  const createMultiPool = async (count: number, supply: bigint, feeBps = 0) => {
+    const mints = (
+      await Promise.all(
+        Array.from({ length: count }, () =>
+          createMint(connection, payer, payer.publicKey, null, 6)
+        )
+      )
+    ).sort((a, b) => a.toBuffer().compare(b.toBuffer()));
+
+    const multiPool = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("multi_pool"),
+        createHash("sha256")
+          .update(Buffer.concat(mints.map((mint) => mint.toBuffer())))
+          .digest(),
+      ],
+      program.programId
+    )[0];
+    const seed = (prefix: string, ...keys: PublicKey[]) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from(prefix),
+          multiPool.toBuffer(),
+          ...keys.map((key) => key.toBuffer()),
+        ],
+        program.programId
+      )[0];
+
+    const reserves = mints.map((mint) => seed("multi_reserve", mint));
+    const userAccounts = [];
+    for (const mint of mints) {
+      const ata = await createAssociatedTokenAccount(
+        connection,
+        payer,
+        mint,
+        payer.publicKey
+      );
+      await mintTo(connection, payer, mint, ata, payer, supply);
+      userAccounts.push(ata);
+    }
+
+    const lpMint = seed("multi_lp_mint");
+    const accounts = {
+      multiPool,
+      lpMint,
+      tokenLpAccount: getAssociatedTokenAddressSync(lpMint, payer.publicKey),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      signer: payer.publicKey,
+    };
+
+    await program.methods
+      .initializeMultiPool(mints, feeBps)
+      .accounts(accounts)
+      .remainingAccounts(
+        [...mints, ...reserves].map((pubkey) => meta(pubkey, true))
+      )
+      .rpc();
+
+    return { ...accounts, mints, reserves, userAccounts };
+  };
+
+  // amountOut = (reserveOut * amountIn) / (reserveIn + amountIn)
+  const expectedSwapOutput = (
+    amountIn: bigint,
+    reserveIn: bigint,
+    reserveOut: bigint
+  ) => (reserveOut * amountIn) / (reserveIn + amountIn);
+
+  const afterFee = (amountIn: bigint, feeBps: number) =>
+    (amountIn * BigInt(10_000 - feeBps)) / 10_000n;
+
+  // 2. Tests
+  it("Swaps between 9-decimal reserves without overflowing", async () => {
+    // 1B tokens at 9 decimals per side - reserve * quantity overflows u64
+    const reserve = 1_000_000_000n * 1_000_000_000n;
+    const pool = await createPool(9, reserve * 2n);
+
+    await deposit(pool, reserve, reserve);
+    assert.equal(await balance(pool.tokenLpAccount), reserve);
+
+    const quantity = 1_000_000n * 1_000_000_000n;
+    const before = await balance(pool.tokenBAccount);
+    await swap(pool, quantity, true);
+
+    assert.equal(
+      (await balance(pool.tokenBAccount)) - before,
+      expectedSwapOutput(quantity, reserve, reserve)
+    );
+    assert.equal(await balance(pool.reserveA), reserve + quantity);
+  });
+
+  it("Deposits and withdraws u64::MAX of each token", async () => {
+    const pool = await createPool(0, U64_MAX);
+
+    // LP[minted] = Sqrt(u64::MAX X u64::MAX) = u64::MAX
+    await deposit(pool, U64_MAX, U64_MAX);
+    assert.equal(await balance(pool.tokenLpAccount), U64_MAX);
+
+    await withdraw(pool, U64_MAX);
+    assert.equal(await balance(pool.tokenAAccount), U64_MAX);
+    assert.equal(await balance(pool.tokenBAccount), U64_MAX);
+    assert.equal(await balance(pool.reserveA), 0n);
+    assert.equal(await balance(pool.reserveB), 0n);
+  });
+
+  it("Swaps up to a u64::MAX input reserve", async () => {
+    const half = 1n << 63n;
+    const pool = await createPool(0, U64_MAX);
+
+    await deposit(pool, half, half);
+
+    // reserve_a ends at exactly u64::MAX
+    const quantity = U64_MAX - half;
+    const before = await balance(pool.tokenBAccount);
+    await swap(pool, quantity, true);
+
+    assert.equal(await balance(pool.reserveA), U64_MAX);
+    assert.equal(
+      (await balance(pool.tokenBAccount)) - before,
+      expectedSwapOutput(quantity, half, half)
+    );
+  });
+
+  it("Withdraws proportionally from near-max reserves", async () => {
+    const pool = await createPool(0, U64_MAX);
+    const quantity = U64_MAX - 1n;
+
+    await deposit(pool, quantity, quantity);
+    const lpSupply = await balance(pool.tokenLpAccount);
+
+    // amount = (shares * reserve) / totalSupply with shares * reserve > u64::MAX
+    const shares = lpSupply / 3n;
+    await withdraw(pool, shares);
+
+    assert.equal(
+      await balance(pool.reserveA),
+      quantity - (shares * quantity) / lpSupply
+    );
+  });
+
+  it("Deposits a single token through the pool", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+
+    // s = Sqrt(100M X 144M) - 100M = 20M A swapped for 66.66M B
+    const lpBefore = await balance(pool.tokenLpAccount);
+    const bBefore = await balance(pool.tokenBAccount);
+    await depositSingle(pool, 44_000_000n, true, 1n);
+
+    // LP[minted] = 24M / 120M X 200M = 40M, floored on the B side
+    const minted = (await balance(pool.tokenLpAccount)) - lpBefore;
+    assert.isTrue(minted > 39_999_000n && minted <= 40_000_000n);
+    assert.equal(await balance(pool.reserveA), 144_000_000n);
+    assert.equal(await balance(pool.reserveB), 400_000_000n);
+    assert.equal(await balance(pool.tokenBAccount), bBefore);
+  });
+
+  it("Rejects a single token deposit below min_lp_out", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+
+    try {
+      await depositSingle(pool, 44_000_000n, true, 40_000_001n);
+      assert.fail("deposit should exceed slippage");
+    } catch (err) {
+      assert.include(String(err), "SlippageExceeded");
+    }
+  });
+
+  it("Withdraws into a single token through the pool", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+
+    // 50M of 200M LP releases 25M A and 100M B
+    // 25M A swapped against 75M A / 300M B returns 75M B
+    const aBefore = await balance(pool.tokenAAccount);
+    const bBefore = await balance(pool.tokenBAccount);
+    await withdrawSingle(pool, 50_000_000n, false, 175_000_000n);
+
+    assert.equal((await balance(pool.tokenBAccount)) - bBefore, 175_000_000n);
+    assert.equal(await balance(pool.tokenAAccount), aBefore);
+    assert.equal(await balance(pool.reserveA), 100_000_000n);
+    assert.equal(await balance(pool.reserveB), 225_000_000n);
+  });
+
+  it("Rejects a single token withdrawal below min_amount_out", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+
+    try {
+      await withdrawSingle(pool, 50_000_000n, false, 175_000_001n);
+      assert.fail("withdrawal should exceed slippage");
+    } catch (err) {
+      assert.include(String(err), "SlippageExceeded");
+    }
+  });
+
+  it("Rejects closing a pool with liquidity", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+
+    try {
+      await closePool(pool);
+      assert.fail("pool should not close");
+    } catch (err) {
+      assert.include(String(err), "PoolNotEmpty");
+    }
+  });
+
+  it("Closes an empty pool and re-creates the pair", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+    await swap(pool, 3_333_333n, true);
+    await withdraw(pool, await balance(pool.tokenLpAccount));
+
+    // tokens sent straight to a reserve are swept back as dust
+    await transfer(
+      connection,
+      payer,
+      pool.tokenAAccount,
+      pool.reserveA,
+      payer,
+      7n
+    );
+
+    await closePool(pool);
+    assert.equal(await balance(pool.tokenAAccount), 1_000_000_000n);
+    for (const address of [pool.amm, pool.reserveA, pool.reserveB]) {
+      assert.isNull(await connection.getAccountInfo(address));
+    }
+
+    await initialize(pool);
+    assert.isNotNull(await connection.getAccountInfo(pool.amm));
+  });
+
+  it("Records creator and creation metadata", async () => {
+    const slot = await connection.getSlot();
+    const pool = await createPool(6, 1_000_000_000n);
+
+    const amm = await program.account.amm.fetch(pool.amm);
+    assert.equal(amm.version, 2);
+    assert.isTrue(amm.creator.equals(payer.publicKey));
+    assert.isAtLeast(amm.createdSlot.toNumber(), slot);
+    assert.isAbove(amm.createdAt.toNumber(), 0);
+  });
+
+  it("Rejects migrating a pool already on the latest version", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+
+    try {
+      await migratePool(pool);
+      assert.fail("pool should already be migrated");
+    } catch (err) {
+      assert.include(String(err), "AlreadyMigrated");
+    }
+  });
+
+  it("Migrates a pool from the original layout", async () => {
+    // tests/fixtures/amm_v0*.json, loaded by the test validator - a version 0
+    // pool (216 bytes) with 200 LP tokens outstanding, and its mints
+    const pool = poolAccounts(
+      new PublicKey("7WdfRFU8GL6N9Dx9QrS1NLtXcE5iUJ6TFdcRTCawidFH"),
+      new PublicKey("DpF4bHHdA3rTC2gUZvXfFARNVjM4U9UySkcYMJwQRSbS")
+    );
+    assert.equal((await connection.getAccountInfo(pool.amm)).data.length, 216);
+
+    await migratePool(pool);
+
+    const space = program.account.amm.size;
+    const migrated = await connection.getAccountInfo(pool.amm);
+    assert.equal(migrated.data.length, space);
+    assert.isAtLeast(
+      migrated.lamports,
+      await connection.getMinimumBalanceForRentExemption(space)
+    );
+
+    // the original fields are kept in place
+    const amm = await program.account.amm.fetch(pool.amm);
+    assert.isTrue(amm.tokenAMint.equals(pool.tokenAMint));
+    assert.isTrue(amm.tokenBMint.equals(pool.tokenBMint));
+    assert.isTrue(amm.lpMint.equals(pool.lpMint));
+    assert.isTrue(amm.reserveA.equals(pool.reserveA));
+    assert.isTrue(amm.reserveB.equals(pool.reserveB));
+    assert.isTrue(amm.poolAuthority.equals(pool.poolAuthority));
+    assert.equal(amm.lpSupply.toString(), "200000000");
+
+    // and the ones added since are filled with defaults
+    assert.equal(amm.version, 2);
+    assert.isTrue(amm.creator.equals(payer.publicKey));
+    assert.equal(amm.createdSlot.toNumber(), 0);
+    assert.equal(amm.lpDecimals, 6);
+    assert.equal(amm.minFeeBps, 0);
+    assert.isTrue(amm.oracle.equals(PublicKey.default));
+    assert.equal(amm.launchState, 0);
+    assert.equal(amm.batchWindowSlots.toNumber(), 0);
+    assert.equal(amm.feeGrowthA.toString(), "0");
+
+    await expectError(migratePool(pool), "AlreadyMigrated");
+  });
+
+  it("Names the LP token after the pair", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    const pair = [pool.tokenAMint, pool.tokenBMint]
+      .map((mint) => mint.toBase58().slice(0, 4))
+      .join("-");
+
+    const metadata = await connection.getAccountInfo(pool.lpMetadata);
+    assert.include(metadata.data.toString("utf8"), `${pair} LP`);
+  });
+
+  it("Lets only the creator update the LP metadata", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    const uri = "https://example.com/lp-updated.json";
+    const updateLpMetadata = (creator: Keypair) =>
+      program.methods
+        .updateLpMetadata("A-B LP", "A-B-LP", uri)
+        .accounts({ ...pool, creator: creator.publicKey })
+        .signers([creator])
+        .rpc();
+
+    await expectError(updateLpMetadata(Keypair.generate()), "Unauthorized");
+
+    await updateLpMetadata(payer);
+    const metadata = await connection.getAccountInfo(pool.lpMetadata);
+    assert.include(metadata.data.toString("utf8"), uri);
+    assert.include(metadata.data.toString("utf8"), "A-B LP");
+    assert.include(metadata.data.toString("utf8"), "A-B-LP");
+  });
+
+  for (const [decimalsA, decimalsB] of [
+    [0, 0],
+    [6, 6],
+    [9, 9],
+    [0, 9],
+    [6, 9],
+  ]) {
+    it(`Derives LP decimals for a ${decimalsA}/${decimalsB}-decimal pair`, async () => {
+      const lpDecimals = Math.max(decimalsA, decimalsB);
+      const pool = await createPool(decimalsA, U64_MAX, decimalsB);
+
+      const lpMint = await getMint(connection, pool.lpMint);
+      const amm = await program.account.amm.fetch(pool.amm);
+      assert.equal(lpMint.decimals, lpDecimals);
+      assert.equal(amm.lpDecimals, lpDecimals);
+
+      // Sqrt(1000 X 4000) = 2000 whole LP tokens
+      await deposit(
+        pool,
+        1000n * 10n ** BigInt(decimalsA),
+        4000n * 10n ** BigInt(decimalsB)
+      );
+      assert.equal(
+        await balance(pool.tokenLpAccount),
+        2000n * 10n ** BigInt(lpDecimals)
+      );
+    });
+  }
+
+  it("Creates missing token accounts for a first-time user", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+
+    // the new user only holds SOL and token A
+    const user = Keypair.generate();
+    const userAccounts = {
+      ...pool,
+      tokenAAccount: getAssociatedTokenAddressSync(
+        pool.tokenAMint,
+        user.publicKey
+      ),
+      tokenBAccount: getAssociatedTokenAddressSync(
+        pool.tokenBMint,
+        user.publicKey
+      ),
+      tokenLpAccount: getAssociatedTokenAddressSync(
+        pool.lpMint,
+        user.publicKey
+      ),
+      signer: user.publicKey,
+    };
+
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: payer.publicKey,
+          toPubkey: user.publicKey,
+          lamports: LAMPORTS_PER_SOL,
+        })
+      )
+    );
+    await createAssociatedTokenAccount(
+      connection,
+      payer,
+      pool.tokenAMint,
+      user.publicKey
+    );
+    await transfer(
+      connection,
+      payer,
+      pool.tokenAAccount,
+      userAccounts.tokenAAccount,
+      payer,
+      10_000_000n
+    );
+
+    // swap output lands in a freshly created B account
+    await program.methods
+      .swap(bn(5_000_000n), true)
+      .accounts(userAccounts)
+      .signers([user])
+      .rpc();
+    const received = await balance(userAccounts.tokenBAccount);
+    assert.isAbove(Number(received), 0);
+
+    // the LP account is created on deposit, closed by a full withdraw and
+    // created again by the next deposit
+    for (let round = 0; round < 2; round++) {
+      await program.methods
+        .depositSingle(bn(1_000_000n), true, bn(1n))
+        .accounts(userAccounts)
+        .signers([user])
+        .rpc();
+
+      const lpBalance = await balance(userAccounts.tokenLpAccount);
+      await program.methods
+        .withdraw(bn(lpBalance))
+        .accounts(userAccounts)
+        .signers([user])
+        .rpc();
+      assert.isNull(
+        await connection.getAccountInfo(userAccounts.tokenLpAccount)
+      );
+    }
+  });
+
+  it("Wraps and unwraps native SOL", async () => {
+    const mintB = await createMint(
+      connection,
+      payer,
+      payer.publicKey,
+      null,
+      9
+    );
+    const pool = poolAccounts(NATIVE_MINT, mintB);
+
+    await createAssociatedTokenAccount(
+      connection,
+      payer,
+      mintB,
+      payer.publicKey
+    );
+    await mintTo(
+      connection,
+      payer,
+      mintB,
+      pool.tokenBAccount,
+      payer,
+      1_000n * BigInt(LAMPORTS_PER_SOL)
+    );
+    await initialize(pool);
+
+    // wSOL the wallet already holds must survive the `_sol` instructions
+    const lamports = BigInt(LAMPORTS_PER_SOL);
+    const held = lamports / 2n;
+    await createWrappedNativeAccount(
+      connection,
+      payer,
+      payer.publicKey,
+      Number(held)
+    );
+    const tempWsolAccount = PublicKey.findProgramAddressSync(
+      [Buffer.from("wsol"), payer.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods
+      .depositSol(bn(lamports), bn(400n * lamports))
+      .accounts({ ...pool, tempWsolAccount })
+      .rpc();
+
+    // the deposit was wrapped into an account closed within the instruction
+    assert.equal(await balance(pool.reserveA), lamports);
+    assert.equal(await balance(pool.tokenAAccount), held);
+    assert.isNull(await connection.getAccountInfo(tempWsolAccount));
+
+    const bBefore = await balance(pool.tokenBAccount);
+    await program.methods
+      .swapSol(bn(lamports / 10n), true)
+      .accounts({ ...pool, tempWsolAccount })
+      .rpc();
+    assert.equal(
+      (await balance(pool.tokenBAccount)) - bBefore,
+      expectedSwapOutput(lamports / 10n, lamports, 400n * lamports)
+    );
+    assert.equal(await balance(pool.tokenAAccount), held);
+    assert.isNull(await connection.getAccountInfo(tempWsolAccount));
+
+    const solBefore = await connection.getBalance(payer.publicKey);
+    await program.methods
+      .withdrawSol(bn(await balance(pool.tokenLpAccount)))
+      .accounts({ ...pool, tempWsolAccount })
+      .rpc();
+
+    // only the withdrawn wSOL was unwrapped, through an account that is
+    // closed again within the instruction
+    assert.equal(await balance(pool.reserveA), 0n);
+    assert.equal(await balance(pool.tokenAAccount), held);
+    assert.isNull(await connection.getAccountInfo(tempWsolAccount));
+    assert.isAbove(
+      await connection.getBalance(payer.publicKey),
+      solBefore + LAMPORTS_PER_SOL
+    );
+  });
+
+  it("Distributes farm rewards to staked LP tokens", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+
+    const rewardMint = await createMint(
+      connection,
+      payer,
+      payer.publicKey,
+      null,
+      6
+    );
+    const farm = farmAccounts(pool, rewardMint);
+    const funderTokenAccount = await createAssociatedTokenAccount(
+      connection,
+      payer,
+      rewardMint,
+      payer.publicKey
+    );
+    await mintTo(
+      connection,
+      payer,
+      rewardMint,
+      funderTokenAccount,
+      payer,
+      1_000_000n
+    );
+
+    await program.methods.initializeFarm().accounts(farm).rpc();
+    await program.methods.addFarmReward().accounts(farm).rpc();
+    await program.methods
+      .fundFarmReward(0, bn(1_000_000n), bn(1_000n))
+      .accounts({ ...farm, funderTokenAccount })
+      .rpc();
+
+    const lp = await balance(pool.tokenLpAccount);
+    await program.methods.stakeLp(bn(lp)).accounts(farm).rpc();
+    assert.equal(await balance(farm.lpVault), lp);
+
+    await sleep(3_000);
+    await program.methods.unstakeLp(bn(lp)).accounts(farm).rpc();
+    assert.equal(await balance(pool.tokenLpAccount), lp);
+
+    // rewards earned while staked survive unstaking
+    const position = await program.account.farmPosition.fetch(farm.position);
+    const owed = BigInt(position.rewardsOwed[0].toString());
+    assert.isAtLeast(Number(owed), 1_000);
+
+    const before = await balance(funderTokenAccount);
+    await program.methods.harvest(0).accounts(farm).rpc();
+    assert.equal((await balance(funderTokenAccount)) - before, owed);
+
+    // nothing accrues once the farm is empty
+    await sleep(1_000);
+    await program.methods.harvest(0).accounts(farm).rpc();
+    assert.equal((await balance(funderTokenAccount)) - before, owed);
+  });
+
+  it("Rejects farm rewards from anyone but the pool creator", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    const farm = farmAccounts(pool, pool.tokenAMint);
+    await program.methods.initializeFarm().accounts(farm).rpc();
+
+    const other = Keypair.generate();
+    try {
+      await program.methods
+        .addFarmReward()
+        .accounts({ ...farm, authority: other.publicKey })
+        .signers([other])
+        .rpc();
+      assert.fail("reward should not be added");
+    } catch (err) {
+      assert.include(String(err), "Unauthorized");
+    }
+  });
+
+  it("Locks LP tokens until the unlock time", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+    const lp = await balance(pool.tokenLpAccount);
+    const lock = lockAccounts(pool, 0n);
+
+    await program.methods
+      .lockLp(bn(0n), bn(lp), bn((await chainTime()) + 3n), bn(0n))
+      .accounts(lock)
+      .rpc();
+    assert.equal(await balance(lock.lockVault), lp);
+
+    try {
+      await program.methods.unlockLp().accounts(lock).rpc();
+      assert.fail("lock should still be active");
+    } catch (err) {
+      assert.include(String(err), "LpLocked");
+    }
+
+    // locks are listed per pool by filtering on the amm field
+    const locks = await program.account.lpLock.all([
+      { memcmp: { offset: 8, bytes: pool.amm.toBase58() } },
+    ]);
+    assert.equal(locks.length, 1);
+    assert.isTrue(locks[0].publicKey.equals(lock.lock));
+
+    await sleep(5_000);
+    await program.methods.unlockLp().accounts(lock).rpc();
+    assert.equal(await balance(pool.tokenLpAccount), lp);
+    assert.equal(await balance(lock.lockVault), 0n);
+  });
+
+  it("Vests locked LP tokens linearly after the unlock time", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+    const lp = await balance(pool.tokenLpAccount);
+    const lock = lockAccounts(pool, 7n);
+
+    await program.methods
+      .lockLp(bn(7n), bn(lp), bn((await chainTime()) + 1n), bn(20n))
+      .accounts(lock)
+      .rpc();
+
+    await sleep(4_000);
+    await program.methods.unlockLp().accounts(lock).rpc();
+    const partial = await balance(pool.tokenLpAccount);
+    assert.isAbove(Number(partial), 0);
+    assert.isBelow(Number(partial), Number(lp));
+
+    const stored = await program.account.lpLock.fetch(lock.lock);
+    assert.equal(BigInt(stored.withdrawn.toString()), partial);
+  });
+
+  it("Charges the configured fixed swap fee", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+    await setFeeConfig(pool, 30, 30, false);
+
+    const bBefore = await balance(pool.tokenBAccount);
+    await swap(pool, 10_000_000n, true);
+
+    // the whole input lands in the reserve, only the output is reduced
+    assert.equal(
+      (await balance(pool.tokenBAccount)) - bBefore,
+      expectedSwapOutput(afterFee(10_000_000n, 30), 100_000_000n, 400_000_000n)
+    );
+    assert.equal(await balance(pool.reserveA), 110_000_000n);
+  });
+
+  it("Raises the dynamic fee after a volatile swap", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 100_000_000n);
+    await setFeeConfig(pool, 10, 500, true);
+
+    await swap(pool, 20_000_000n, true);
+    const reserveA = await balance(pool.reserveA);
+    const reserveB = await balance(pool.reserveB);
+
+    const aBefore = await balance(pool.tokenAAccount);
+    await swap(pool, 1_000_000n, false);
+    const received = (await balance(pool.tokenAAccount)) - aBefore;
+
+    // the first swap moved the price ~30%, so the second pays far above min
+    assert.isBelow(
+      Number(received),
+      Number(expectedSwapOutput(afterFee(1_000_000n, 200), reserveB, reserveA))
+    );
+
+    const amm = await program.account.amm.fetch(pool.amm);
+    assert.isAbove(amm.volatility.toNumber(), 0);
+  });
+
+  it("Rejects invalid or unauthorized fee configs", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+
+    for (const [min, max] of [
+      [50, 20],
+      [0, 5_000],
+    ]) {
+      try {
+        await setFeeConfig(pool, min, max, true);
+        assert.fail("fee config should be rejected");
+      } catch (err) {
+        assert.include(String(err), "InvalidFee");
+      }
+    }
+
+    const other = Keypair.generate();
+    try {
+      await program.methods
+        .setFeeConfig(30, 30, false)
+        .accounts({ ...pool, creator: other.publicKey })
+        .signers([other])
+        .rpc();
+      assert.fail("fee config should be rejected");
+    } catch (err) {
+      assert.include(String(err), "Unauthorized");
+    }
+  });
+
+  it("Trades any-to-any in a multi-asset pool", async () => {
+    const pool = await createMultiPool(3, 1_000_000_000n, 30);
+    const all = [...pool.reserves, ...pool.userAccounts].map((pubkey) =>
+      meta(pubkey, true)
+    );
+
+    // first deposit mints the sum of whole tokens
+    await program.methods
+      .depositMulti([100_000_000n, 100_000_000n, 100_000_000n].map(bn), bn(1n))
+      .accounts(pool)
+      .remainingAccounts(all)
+      .rpc();
+    assert.equal(await balance(pool.tokenLpAccount), 300_000_000n);
+
+    // later deposits only take each reserve's share of the minted LP tokens
+    await program.methods
+      .depositMulti([10_000_000n, 50_000_000n, 20_000_000n].map(bn), bn(1n))
+      .accounts(pool)
+      .remainingAccounts(all)
+      .rpc();
+    assert.equal(await balance(pool.tokenLpAccount), 330_000_000n);
+    for (const reserve of pool.reserves) {
+      assert.equal(await balance(reserve), 110_000_000n);
+    }
+
+    const before = await balance(pool.userAccounts[0]);
+    await program.methods
+      .swapMulti(2, 0, bn(11_000_000n), bn(1n))
+      .accounts(pool)
+      .remainingAccounts(
+        [
+          pool.reserves[2],
+          pool.reserves[0],
+          pool.userAccounts[2],
+          pool.userAccounts[0],
+        ].map((pubkey) => meta(pubkey, true))
+      )
+      .rpc();
+    assert.equal(
+      (await balance(pool.userAccounts[0])) - before,
+      expectedSwapOutput(afterFee(11_000_000n, 30), 110_000_000n, 110_000_000n)
+    );
+    assert.equal(await balance(pool.reserves[1]), 110_000_000n);
+
+    try {
+      await program.methods
+        .swapMulti(0, 1, bn(1_000n), bn(1n))
+        .accounts(pool)
+        .remainingAccounts(
+          [
+            pool.reserves[2],
+            pool.reserves[1],
+            pool.userAccounts[0],
+            pool.userAccounts[1],
+          ].map((pubkey) => meta(pubkey, true))
+        )
+        .rpc();
+      assert.fail("swap should reject a mismatched reserve");
+    } catch (err) {
+      assert.include(String(err), "InvalidPoolAccounts");
+    }
+
+    await program.methods
+      .withdrawMulti(bn(await balance(pool.tokenLpAccount)))
+      .accounts(pool)
+      .remainingAccounts(all)
+      .rpc();
+    for (const reserve of pool.reserves) {
+      assert.equal(await balance(reserve), 0n);
+    }
+  });
+
+  it("Deposits and withdraws one asset of a multi-asset pool", async () => {
+    const pool = await createMultiPool(4, 1_000_000_000n);
+    const all = [...pool.reserves, ...pool.userAccounts].map((pubkey) =>
+      meta(pubkey, true)
+    );
+    const single = (i: number) =>
+      [pool.reserves[i], pool.userAccounts[i]].map((pubkey) =>
+        meta(pubkey, true)
+      );
+
+    await program.methods
+      .depositMulti(pool.mints.map(() => bn(100_000_000n)), bn(1n))
+      .accounts(pool)
+      .remainingAccounts(all)
+      .rpc();
+
+    // LP[minted] = 400M X ((1 + 46.41M / 100M)^(1/4) - 1) = 40M
+    const lpBefore = await balance(pool.tokenLpAccount);
+    await program.methods
+      .depositMultiSingle(1, bn(46_410_000n), bn(1n))
+      .accounts(pool)
+      .remainingAccounts(single(1))
+      .rpc();
+    const minted = (await balance(pool.tokenLpAccount)) - lpBefore;
+    assert.isTrue(minted > 39_999_000n && minted <= 40_000_000n);
+
+    // burning the same LP tokens returns almost exactly the deposit
+    const before = await balance(pool.userAccounts[1]);
+    await program.methods
+      .withdrawMultiSingle(1, bn(minted), bn(1n))
+      .accounts(pool)
+      .remainingAccounts(single(1))
+      .rpc();
+    const returned = (await balance(pool.userAccounts[1])) - before;
+    assert.isTrue(returned <= 46_410_000n && returned > 46_400_000n);
+
+    try {
+      await program.methods
+        .withdrawMultiSingle(0, bn(1_000n), bn(U64_MAX))
+        .accounts(pool)
+        .remainingAccounts(single(0))
+        .rpc();
+      assert.fail("withdrawal should hit the slippage limit");
+    } catch (err) {
+      assert.include(String(err), "SlippageExceeded");
+    }
+  });
+
+  it("Guards swaps with an oracle price band", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+
+    // 4.00 B per A
+    const oracle = await createPriceFeed(1n, 400n, -2);
+    await program.methods
+      .setPoolOracle(500, bn(60n))
+      .accounts({ ...pool, oracle, creator: payer.publicKey })
+      .rpc();
+
+    // ~2% move stays inside the 5% band
+    await program.methods
+      .swap(bn(1_000_000n), true)
+      .accounts({ ...pool, oracle })
+      .rpc();
+
+    const expectError = async (
+      quantity: bigint,
+      accounts: Pool & { oracle?: PublicKey },
+      error: string
+    ) => {
+      try {
+        await program.methods.swap(bn(quantity), true).accounts(accounts).rpc();
+        assert.fail("swap should be rejected");
+      } catch (err) {
+        assert.include(String(err), error);
+      }
+    };
+
+    await expectError(
+      10_000_000n,
+      { ...pool, oracle },
+      "PriceDeviationExceeded"
+    );
+    await expectError(1_000n, pool, "InvalidOracle");
+
+    await program.methods
+      .setPoolOracle(500, bn(1n))
+      .accounts({ ...pool, oracle, creator: payer.publicKey })
+      .rpc();
+    await sleep(3_000);
+    await expectError(1_000n, { ...pool, oracle }, "OracleStale");
+
+    // removing the oracle lifts the guard
+    await program.methods
+      .setPoolOracle(0, bn(0n))
+      .accounts({ ...pool, oracle: null, creator: payer.publicKey })
+      .rpc();
+    await swap(pool, 10_000_000n, true);
+  });
+
+  it("Splits the swap fee with a referrer", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+    await setFeeConfig(pool, 100, 100, false);
+
+    // referrers may take up to 20% of the fee
+    await program.methods
+      .initializeConfig(2_000)
+      .accounts({
+        config,
+        program: program.programId,
+        programData,
+        systemProgram: SystemProgram.programId,
+        signer: payer.publicKey,
+      })
+      .rpc();
+
+    const setReferralFee = (bps: number) =>
+      program.methods
+        .setReferralFee(bps)
+        .accounts({ ...pool, config, creator: payer.publicKey })
+        .rpc();
+
+    try {
+      await setReferralFee(5_000);
+      assert.fail("referral fee should be capped");
+    } catch (err) {
+      assert.include(String(err), "ReferralFeeTooHigh");
+    }
+    await setReferralFee(2_000);
+
+    const partner = Keypair.generate();
+    const referrerTokenAccount = await createAssociatedTokenAccount(
+      connection,
+      payer,
+      pool.tokenAMint,
+      partner.publicKey
+    );
+
+    const referrer = PublicKey.findProgramAddressSync(
+      [Buffer.from("referrer"), partner.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    // only wallets registered by the config authority earn referrals
+    try {
+      await program.methods
+        .swap(bn(10_000_000n), true)
+        .accounts({ ...pool, referrerTokenAccount, config })
+        .rpc();
+      assert.fail("unregistered referrer should be rejected");
+    } catch (err) {
+      assert.include(String(err), "InvalidReferrer");
+    }
+
+    await program.methods
+      .registerReferrer()
+      .accounts({
+        config,
+        referrer,
+        owner: partner.publicKey,
+        systemProgram: SystemProgram.programId,
+        authority: payer.publicKey,
+      })
+      .rpc();
+
+    const signature = await program.methods
+      .swap(bn(10_000_000n), true)
+      .accounts({ ...pool, referrerTokenAccount, referrer, config })
+      .rpc({ commitment: "confirmed" });
+
+    // fee = 100k, referral = 20% of it
+    assert.equal(await balance(referrerTokenAccount), 20_000n);
+    assert.equal(await balance(pool.reserveA), 109_980_000n);
+
+    const tx = await connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    const events = [...parser.parseLogs(tx.meta.logMessages)];
+    assert.equal(events.length, 1);
+    assert.equal(events[0].name, "SwapReferral");
+    assert.equal(events[0].data.referralAmount.toString(), "20000");
+    assert.equal(events[0].data.swapFee.toString(), "100000");
+
+    // a registered referrer cannot take a cut of their own swaps
+    const selfReferrer = PublicKey.findProgramAddressSync(
+      [Buffer.from("referrer"), payer.publicKey.toBuffer()],
+      program.programId
+    )[0];
+    await program.methods
+      .registerReferrer()
+      .accounts({
+        config,
+        referrer: selfReferrer,
+        owner: payer.publicKey,
+        systemProgram: SystemProgram.programId,
+        authority: payer.publicKey,
+      })
+      .rpc();
+
+    try {
+      await program.methods
+        .swap(bn(10_000_000n), true)
+        .accounts({
+          ...pool,
+          referrerTokenAccount: pool.tokenAAccount,
+          referrer: selfReferrer,
+          config,
+        })
+        .rpc();
+      assert.fail("self-referral should be rejected");
+    } catch (err) {
+      assert.include(String(err), "SelfReferral");
+    }
+  });
+
+  it("Restricts permissioned pools to allowlisted wallets", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+
+    const desk = Keypair.generate();
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: payer.publicKey,
+          toPubkey: desk.publicKey,
+          lamports: LAMPORTS_PER_SOL,
+        })
+      )
+    );
+    await program.methods
+      .setAllowlistAuthority(desk.publicKey)
+      .accounts({ ...pool, creator: payer.publicKey })
+      .rpc();
+
+    const expectNotAllowlisted = async (promise: Promise<string>) => {
+      try {
+        await promise;
+        assert.fail("signer should not be allowed");
+      } catch (err) {
+        assert.include(String(err), "NotAllowlisted");
+      }
+    };
+    await expectNotAllowlisted(swap(pool, 1_000_000n, true));
+    await expectNotAllowlisted(deposit(pool, 1_000_000n, 4_000_000n));
+
+    // the authority can co-sign a single trade
+    await program.methods
+      .swap(bn(1_000_000n), true)
+      .accounts({ ...pool, allowlistAuthority: desk.publicKey })
+      .signers([desk])
+      .rpc();
+
+    const allowlistEntry = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("allowlist"),
+        pool.amm.toBuffer(),
+        payer.publicKey.toBuffer(),
+      ],
+      program.programId
+    )[0];
+    const entryAccounts = {
+      ...pool,
+      allowlistEntry,
+      wallet: payer.publicKey,
+      allowlistAuthority: desk.publicKey,
+    };
+
+    await program.methods
+      .addAllowlistEntry()
+      .accounts(entryAccounts)
+      .signers([desk])
+      .rpc();
+
+    await program.methods
+      .swap(bn(1_000_000n), false)
+      .accounts({ ...pool, allowlistEntry })
+      .rpc();
+    await program.methods
+      .withdraw(bn(1_000n))
+      .accounts({ ...pool, allowlistEntry })
+      .rpc();
+
+    await program.methods
+      .removeAllowlistEntry()
+      .accounts(entryAccounts)
+      .signers([desk])
+      .rpc();
+    assert.isNull(await connection.getAccountInfo(allowlistEntry));
+    await expectNotAllowlisted(withdraw(pool, 1_000n));
+  });
+  it("Launches a pool on virtual reserves and graduates it", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    const [launch, launchReserveA, launchReserveB] = [
+      "launch",
+      "launch_reserve_a",
+      "launch_reserve_b",
+    ].map(
+      (seed) =>
+        PublicKey.findProgramAddressSync(
+          [
+            Buffer.from(seed),
+            pool.tokenAMint.toBuffer(),
+            pool.tokenBMint.toBuffer(),
+          ],
+          program.programId
+        )[0]
+    );
+    const launchAccounts = { ...pool, launch, launchReserveA, launchReserveB };
+
+    // 800 base tokens priced against 100 virtual quote tokens, graduating
+    // once the launch pool holds 50 real quote tokens
+    await program.methods
+      .initializeLaunch(
+        bn(800_000_000n),
+        bn(100_000_000n),
+        bn(50_000_000n),
+        true
+      )
+      .accounts({
+        ...launchAccounts,
+        creatorBaseAccount: pool.tokenAAccount,
+        creator: payer.publicKey,
+      })
+      .rpc();
+
+    const graduatedLpVault = PublicKey.findProgramAddressSync(
+      [Buffer.from("graduated_lp_vault"), pool.amm.toBuffer()],
+      program.programId
+    )[0];
+    const swapLaunch = (quantity: bigint, isA: boolean) =>
+      program.methods
+        .swapLaunch(bn(quantity), isA)
+        .accounts(launchAccounts)
+        .rpc();
+    const graduate = () =>
+      program.methods
+        .graduate()
+        .accounts({
+          ...launchAccounts,
+          graduatedLpVault,
+          creator: payer.publicKey,
+        })
+        .rpc();
+
+    // the standard pool stays empty until the launch graduates into it
+    await expectError(deposit(pool, 1_000_000n, 1_000_000n), "LaunchActive");
+    await expectError(swap(pool, 1_000_000n, false), "LaunchActive");
+    await expectError(closePool(pool), "LaunchActive");
+
+    // minting more of the launched token doesn't bring graduation closer
+    await mintTo(
+      connection,
+      payer,
+      pool.tokenAMint,
+      pool.tokenAAccount,
+      payer,
+      1_000_000_000_000n
+    );
+    await expectError(graduate(), "GraduationThresholdNotReached");
+
+    // buy: 800 X 50 / (100 + 50) = 266.67 base
+    await swapLaunch(50_000_000n, false);
+    assert.equal(await balance(launchReserveA), 533_333_334n);
+    assert.equal(await balance(launchReserveB), 50_000_000n);
+
+    // selling can only pay out of the real quote reserve
+    await expectError(swapLaunch(400_000_000n, true), "InsufficientReserves");
+
+    // base kept = 533.33 X 50 / 150 = 177.78, the rest is burned
+    const supplyBefore = (await getMint(connection, pool.tokenAMint)).supply;
+    await graduate();
+
+    assert.equal(await balance(pool.reserveA), 177_777_778n);
+    assert.equal(await balance(pool.reserveB), 50_000_000n);
+    assert.equal(
+      (await getMint(connection, pool.tokenAMint)).supply,
+      supplyBefore - 355_555_556n
+    );
+    for (const account of [launch, launchReserveA, launchReserveB]) {
+      assert.isNull(await connection.getAccountInfo(account));
+    }
+
+    const amm = await program.account.amm.fetch(pool.amm);
+    assert.equal(amm.launchState, 2);
+    assert.isTrue(amm.lpSupply.gtn(0));
+    assert.equal(
+      amm.lpSupply.toString(),
+      (await balance(graduatedLpVault)).toString()
+    );
+
+    // a standard pool from here on, kept open by the locked LP
+    await swap(pool, 1_000_000n, false);
+    await expectError(closePool(pool), "PoolNotEmpty");
+  });
+
+  it("Trips the circuit breaker on price moves and outflows", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+
+    const setCircuitBreaker = (
+      windowSlots: bigint,
+      maxPriceChangeBps: number,
+      maxOutflowBps: number
+    ) =>
+      program.methods
+        .setCircuitBreaker(bn(windowSlots), maxPriceChangeBps, maxOutflowBps)
+        .accounts({ ...pool, creator: payer.publicKey })
+        .rpc();
+
+    await expectError(
+      setCircuitBreaker(1_000n, 0, 20_000),
+      "InvalidCircuitBreaker"
+    );
+
+    // at most a 5% price move per window
+    await setCircuitBreaker(1_000n, 500, 0);
+    await swap(pool, 1_000_000n, true); // ~2%
+    await expectError(swap(pool, 2_000_000n, true), "CircuitBreakerTripped");
+    // trading back towards the window's start price is still fine
+    await swap(pool, 4_000_000n, false);
+
+    // at most 10% of a reserve paid out per window
+    await setCircuitBreaker(1_000n, 0, 1_000);
+    await swap(pool, 5_000_000n, true); // ~19 B out
+    await expectError(swap(pool, 7_000_000n, true), "CircuitBreakerTripped");
+
+    await setCircuitBreaker(0n, 0, 0);
+    await swap(pool, 7_000_000n, true);
+  });
+
+  it("Fills limit orders once a swap crosses their price", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+
+    const orderAccounts = (orderId: bigint) => {
+      const order = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("limit_order"),
+          pool.amm.toBuffer(),
+          payer.publicKey.toBuffer(),
+          bn(orderId).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+      const orderVault = PublicKey.findProgramAddressSync(
+        [Buffer.from("limit_order_vault"), order.toBuffer()],
+        program.programId
+      )[0];
+
+      return { order, orderVault };
+    };
+
+    // sell 10 A for at least 38 B - the pool pays 36.4 B right now
+    const { order, orderVault } = orderAccounts(1n);
+    await program.methods
+      .placeLimitOrder(bn(1n), bn(10_000_000n), bn(38_000_000n))
+      .accounts({
+        ...pool,
+        order,
+        orderVault,
+        userTokenAccount: pool.tokenAAccount,
+        inputMint: pool.tokenAMint,
+      })
+      .rpc();
+    assert.equal(await balance(orderVault), 10_000_000n);
+
+    const keeper = Keypair.generate();
+    const [keeperTokenAAccount, keeperTokenBAccount] = await Promise.all(
+      [pool.tokenAMint, pool.tokenBMint].map((mint) =>
+        createAssociatedTokenAccount(connection, payer, mint, keeper.publicKey)
+      )
+    );
+    const fillOrders = () =>
+      program.methods
+        .fillOrders()
+        .accounts({
+          ...pool,
+          keeperTokenAAccount,
+          keeperTokenBAccount,
+          keeper: keeper.publicKey,
+        })
+        .remainingAccounts([
+          meta(order, true),
+          meta(orderVault, true),
+          meta(pool.tokenAAccount, true),
+          meta(pool.tokenBAccount, true),
+          meta(payer.publicKey, true),
+        ])
+        .signers([keeper])
+        .rpc();
+
+    await expectError(fillOrders(), "NoEligibleOrders");
+
+    // buying A pushes its price past the limit
+    await swap(pool, 40_000_000n, false);
+
+    // tokens sent to the vault don't block the fill, they go to the owner
+    await transfer(
+      connection,
+      payer,
+      pool.tokenAAccount,
+      orderVault,
+      payer,
+      1_000n
+    );
+    const aBefore = await balance(pool.tokenAAccount);
+    const balanceBefore = await balance(pool.tokenBAccount);
+    await fillOrders();
+    assert.equal((await balance(pool.tokenAAccount)) - aBefore, 1_000n);
+
+    // out = 440 X 10 / 100.909 = 43.6036 B, 0.1% of it to the keeper
+    assert.equal(await balance(keeperTokenBAccount), 43_603n);
+    assert.equal(
+      (await balance(pool.tokenBAccount)) - balanceBefore,
+      43_560_000n
+    );
+    assert.equal(await balance(pool.reserveA), 100_909_091n);
+    assert.equal(await balance(pool.reserveB), 396_396_397n);
+    assert.isNull(await connection.getAccountInfo(order));
+    assert.isNull(await connection.getAccountInfo(orderVault));
+
+    // cancelling returns the escrowed tokens
+    const second = orderAccounts(2n);
+    const balanceA = await balance(pool.tokenAAccount);
+    await program.methods
+      .placeLimitOrder(bn(2n), bn(5_000_000n), bn(100_000_000n))
+      .accounts({
+        ...pool,
+        ...second,
+        userTokenAccount: pool.tokenAAccount,
+        inputMint: pool.tokenAMint,
+      })
+      .rpc();
+    await program.methods
+      .cancelLimitOrder()
+      .accounts({
+        ...second,
+        ownerTokenAccount: pool.tokenAAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        owner: payer.publicKey,
+      })
+      .rpc();
+    assert.equal(await balance(pool.tokenAAccount), balanceA);
+    assert.isNull(await connection.getAccountInfo(second.order));
+
+    // an order the circuit breaker rejects is skipped without failing the
+    // rest of the crank
+    await program.methods
+      .setCircuitBreaker(bn(1_000n), 500, 0)
+      .accounts({ ...pool, creator: payer.publicKey })
+      .rpc();
+
+    const large = orderAccounts(3n);
+    const small = orderAccounts(4n);
+    for (const [orderId, { order, orderVault }, amountIn] of [
+      [3n, large, 30_000_000n],
+      [4n, small, 1_000_000n],
+    ] as const) {
+      await program.methods
+        .placeLimitOrder(bn(orderId), bn(amountIn), bn(1n))
+        .accounts({
+          ...pool,
+          order,
+          orderVault,
+          userTokenAccount: pool.tokenAAccount,
+          inputMint: pool.tokenAMint,
+        })
+        .rpc();
+    }
+
+    await program.methods
+      .fillOrders()
+      .accounts({
+        ...pool,
+        keeperTokenAAccount,
+        keeperTokenBAccount,
+        keeper: keeper.publicKey,
+      })
+      .remainingAccounts(
+        [large, small].flatMap(({ order, orderVault }) => [
+          meta(order, true),
+          meta(orderVault, true),
+          meta(pool.tokenAAccount, true),
+          meta(pool.tokenBAccount, true),
+          meta(payer.publicKey, true),
+        ])
+      )
+      .signers([keeper])
+      .rpc();
+
+    assert.equal(await balance(large.orderVault), 30_000_000n);
+    assert.isNull(await connection.getAccountInfo(small.order));
+  });
+
+  it("Executes DCA tranches once per interval", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+
+    const dcaAccounts = (dcaId: bigint) => {
+      const dca = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("dca"),
+          pool.amm.toBuffer(),
+          payer.publicKey.toBuffer(),
+          bn(dcaId).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+      const vault = (prefix: string) =>
+        PublicKey.findProgramAddressSync(
+          [Buffer.from(prefix), dca.toBuffer()],
+          program.programId
+        )[0];
+
+      return {
+        ...pool,
+        dca,
+        inputVault: vault("dca_input_vault"),
+        outputVault: vault("dca_output_vault"),
+        userTokenAccount: pool.tokenAAccount,
+        inputMint: pool.tokenAMint,
+        outputMint: pool.tokenBMint,
+        ownerInputAccount: pool.tokenAAccount,
+        ownerOutputAccount: pool.tokenBAccount,
+        owner: payer.publicKey,
+      };
+    };
+
+    const keeper = Keypair.generate();
+    const keeperTokenAccount = await createAssociatedTokenAccount(
+      connection,
+      payer,
+      pool.tokenBMint,
+      keeper.publicKey
+    );
+    const executeDca = (accounts: ReturnType<typeof dcaAccounts>) =>
+      program.methods
+        .executeDca()
+        .accounts({ ...accounts, keeperTokenAccount, keeper: keeper.publicKey })
+        .signers([keeper])
+        .rpc();
+
+    // sell 30 A in tranches of 10, at no less than 3 B per A
+    const dca = dcaAccounts(1n);
+    await program.methods
+      .openDca(
+        bn(1n),
+        bn(30_000_000n),
+        bn(10_000_000n),
+        bn(2n),
+        bn(30_000_000n)
+      )
+      .accounts(dca)
+      .rpc();
+
+    // out = 400 X 10 / 110 = 36.36 B, 0.1% of it to the keeper
+    await executeDca(dca);
+    assert.equal(await balance(dca.outputVault), 36_327_273n);
+    assert.equal(await balance(keeperTokenAccount), 36_363n);
+    await expectError(executeDca(dca), "DcaNotDue");
+
+    await sleep(3_000);
+    await executeDca(dca);
+    assert.equal(await balance(dca.inputVault), 10_000_000n);
+    assert.equal(await balance(dca.outputVault), 66_600_000n);
+
+    const order = await program.account.dcaOrder.fetch(dca.dca);
+    assert.equal(order.totalIn.toString(), "20000000");
+    assert.equal(order.totalOut.toString(), "66600000");
+
+    // a tranche below the minimum price fails until the price recovers
+    const greedy = dcaAccounts(2n);
+    await program.methods
+      .openDca(
+        bn(2n),
+        bn(10_000_000n),
+        bn(10_000_000n),
+        bn(60n),
+        bn(50_000_000n)
+      )
+      .accounts(greedy)
+      .rpc();
+    await expectError(executeDca(greedy), "SlippageExceeded");
+
+    // closing pays out the proceeds and the unsold input
+    const balanceA = await balance(pool.tokenAAccount);
+    const balanceB = await balance(pool.tokenBAccount);
+    await program.methods.closeDca().accounts(dca).rpc();
+    assert.equal(await balance(pool.tokenAAccount), balanceA + 10_000_000n);
+    assert.equal(await balance(pool.tokenBAccount), balanceB + 66_600_000n);
+    assert.isNull(await connection.getAccountInfo(dca.dca));
+  });
+
+  it("Settles batched intents at a single clearing price", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+
+    const windowSlots = 30;
+    await program.methods
+      .setBatchMode(bn(BigInt(windowSlots)), bn(1_000_000n), bn(1_000_000n))
+      .accounts({ ...pool, creator: payer.publicKey })
+      .rpc();
+    await expectError(swap(pool, 1_000_000n, true), "BatchModeActive");
+
+    // two more traders, funded with both tokens
+    const [bob, carol] = [Keypair.generate(), Keypair.generate()];
+    const tokenAccounts = new Map<Keypair, [PublicKey, PublicKey]>();
+    for (const user of [bob, carol]) {
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: payer.publicKey,
+            toPubkey: user.publicKey,
+            lamports: LAMPORTS_PER_SOL,
+          })
+        )
+      );
+      const accounts: [PublicKey, PublicKey] = [null, null];
+      for (const [i, mint] of [pool.tokenAMint, pool.tokenBMint].entries()) {
+        accounts[i] = await createAssociatedTokenAccount(
+          connection,
+          payer,
+          mint,
+          user.publicKey
+        );
+        const source = i == 0 ? pool.tokenAAccount : pool.tokenBAccount;
+        await transfer(
+          connection,
+          payer,
+          source,
+          accounts[i],
+          payer,
+          50_000_000n
+        );
+      }
+      tokenAccounts.set(user, accounts);
+    }
+    tokenAccounts.set(payer, [pool.tokenAAccount, pool.tokenBAccount]);
+
+    const waitForSlot = async (slot: number) => {
+      while ((await connection.getSlot()) < slot) await sleep(400);
+    };
+
+    // leave enough of the window to submit every intent
+    let slot = await connection.getSlot();
+    if (windowSlots - (slot % windowSlots) < 15) {
+      await waitForSlot(slot - (slot % windowSlots) + windowSlots);
+      slot = await connection.getSlot();
+    }
+    const startSlot = slot - (slot % windowSlots);
+
+    const batch = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("batch"),
+        pool.amm.toBuffer(),
+        bn(BigInt(startSlot)).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+    const batchVault = (prefix: string) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from(prefix), batch.toBuffer()],
+        program.programId
+      )[0];
+    const [batchVaultA, batchVaultB] = [
+      batchVault("batch_vault_a"),
+      batchVault("batch_vault_b"),
+    ];
+    const intentOf = (user: Keypair) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("swap_intent"),
+          batch.toBuffer(),
+          user.publicKey.toBuffer(),
+        ],
+        program.programId
+      )[0];
+
+    const submitIntent = (
+      user: Keypair,
+      amountIn: bigint,
+      sellA: boolean,
+      minAmountOut: bigint
+    ) =>
+      program.methods
+        .submitIntent(
+          bn(BigInt(startSlot)),
+          bn(amountIn),
+          sellA,
+          bn(minAmountOut)
+        )
+        .accounts({
+          ...pool,
+          batch,
+          batchVaultA,
+          batchVaultB,
+          intent: intentOf(user),
+          userTokenAccount: tokenAccounts.get(user)[sellA ? 0 : 1],
+          signer: user.publicKey,
+        })
+        .signers([user])
+        .rpc();
+
+    await expectError(
+      submitIntent(payer, 999_999n, true, 0n),
+      "IntentTooSmall"
+    );
+    await submitIntent(payer, 10_000_000n, true, 0n);
+    await submitIntent(bob, 20_000_000n, false, 0n);
+    // carol's limit is far above what the batch can pay
+    await submitIntent(carol, 5_000_000n, true, 100_000_000n);
+
+    const cancelIntent = (user: Keypair, inIndex: number) =>
+      program.methods
+        .cancelIntent()
+        .accounts({
+          batch,
+          intent: intentOf(user),
+          batchVault: inIndex == 0 ? batchVaultA : batchVaultB,
+          ownerTokenAccount: tokenAccounts.get(user)[inIndex],
+          tokenProgram: TOKEN_PROGRAM_ID,
+          owner: user.publicKey,
+        })
+        .signers([user])
+        .rpc();
+
+    // intents can be cancelled while the window is open
+    await cancelIntent(bob, 1);
+    assert.equal(await balance(tokenAccounts.get(bob)[1]), 50_000_000n);
+    await submitIntent(bob, 20_000_000n, false, 0n);
+
+    const settleBatch = (users: Keypair[]) =>
+      program.methods
+        .settleBatch()
+        .accounts({
+          ...pool,
+          batch,
+          batchVaultA,
+          batchVaultB,
+          payer: payer.publicKey,
+        })
+        .remainingAccounts(users.map((user) => meta(intentOf(user), true)))
+        .rpc();
+
+    await expectError(settleBatch([payer, bob, carol]), "BatchNotReady");
+    await waitForSlot(startSlot + windowSlots);
+    // but not once it has closed
+    await expectError(cancelIntent(carol, 0), "BatchClosed");
+
+    // settled in pages: the first pass excludes carol once it has seen every
+    // intent, and the second pass needs payer and bob again
+    await settleBatch([payer, carol]);
+    await settleBatch([bob]);
+    const paged = await program.account.batch.fetch(batch);
+    assert.isFalse(paged.settled);
+    assert.equal(paged.pass, 2);
+    assert.equal(paged.excludedCount, 1);
+    await settleBatch([payer]);
+
+    // without carol: 10 A against 20 B, the net 4.76 A trades on the curve
+    // and both sides clear at 38.18 / 10 = 20 / 5.24 = 3.818 B per A
+    const settled = await program.account.batch.fetch(batch);
+    assert.isTrue(settled.settled);
+    assert.equal(settled.totalOutA.toString(), "5238096");
+    assert.equal(settled.totalOutB.toString(), "38181815");
+    assert.equal(await balance(pool.reserveA), 104_761_904n);
+    assert.equal(await balance(pool.reserveB), 381_818_185n);
+
+    const claim = async (user: Keypair, outIndex: number) => {
+      const ownerTokenAccount = tokenAccounts.get(user)[outIndex];
+      const before = await balance(ownerTokenAccount);
+      await program.methods
+        .claimIntent()
+        .accounts({
+          batch,
+          intent: intentOf(user),
+          batchVaultA,
+          batchVaultB,
+          amm: pool.amm,
+          reserveA: pool.reserveA,
+          reserveB: pool.reserveB,
+          ownerTokenAccount,
+          owner: user.publicKey,
+          payer: payer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      return (await balance(ownerTokenAccount)) - before;
+    };
+
+    assert.equal(await claim(payer, 1), 38_181_815n);
+    assert.equal(await claim(bob, 0), 5_238_096n);
+    // tokens sent to a vault directly don't keep it open
+    await transfer(
+      connection,
+      payer,
+      pool.tokenBAccount,
+      batchVaultB,
+      payer,
+      1_000n
+    );
+    // carol was excluded and gets her A back
+    assert.equal(await claim(carol, 0), 5_000_000n);
+    assert.isNull(await connection.getAccountInfo(intentOf(carol)));
+
+    // the last claim closed the vaults and the batch, and sent the
+    // leftover to the reserve
+    for (const account of [batch, batchVaultA, batchVaultB]) {
+      assert.isNull(await connection.getAccountInfo(account));
+    }
+    assert.equal(await balance(pool.reserveB), 381_819_185n);
+  });
+
+  it("Tracks LP positions held as NFTs", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+    const lpSupply = (await program.account.amm.fetch(pool.amm)).lpSupply;
+
+    const positionMint = Keypair.generate();
+    const position = PublicKey.findProgramAddressSync(
+      [Buffer.from("position"), positionMint.publicKey.toBuffer()],
+      program.programId
+    )[0];
+    const positionTokenAccount = getAssociatedTokenAddressSync(
+      positionMint.publicKey,
+      payer.publicKey
+    );
+
+    await program.methods
+      .depositPosition(bn(10_000_000n), bn(40_000_000n))
+      .accounts({
+        ...pool,
+        position,
+        positionMint: positionMint.publicKey,
+        positionTokenAccount,
+      })
+      .signers([positionMint])
+      .rpc();
+
+    // shares are priced like LP tokens and the NFT can't be minted again
+    const opened = await program.account.position.fetch(position);
+    assert.equal(opened.shares.toString(), lpSupply.divn(10).toString());
+    assert.equal(opened.depositedA.toString(), "10000000");
+    assert.equal(opened.entryPrice.toString(), (4n << 64n).toString());
+    const mint = await getMint(connection, positionMint.publicKey);
+    assert.equal(mint.supply, 1n);
+    assert.equal(mint.decimals, 0);
+    assert.isNull(mint.mintAuthority);
+
+    // trading leaves fees in the pool
+    await setFeeConfig(pool, 30, 30, false);
+    await swap(pool, 20_000_000n, true);
+    await swap(pool, 50_000_000n, false);
+
+    // the position holds 1/11 of the supply and earns that share of the fees
+    const quotePositionFees = () =>
+      program.methods
+        .quotePositionFees()
+        .accounts({ ...pool, position })
+        .view();
+    const fees = await quotePositionFees();
+    assert.closeTo(fees.feesA.toNumber(), 60_000 / 11, 1);
+    assert.closeTo(fees.feesB.toNumber(), 150_000 / 11, 1);
+
+    // the position moves with the NFT
+    const bob = Keypair.generate();
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: payer.publicKey,
+          toPubkey: bob.publicKey,
+          lamports: LAMPORTS_PER_SOL,
+        })
+      )
+    );
+    const bobPositionAccount = await createAssociatedTokenAccount(
+      connection,
+      payer,
+      positionMint.publicKey,
+      bob.publicKey
+    );
+    await transfer(
+      connection,
+      payer,
+      positionTokenAccount,
+      bobPositionAccount,
+      payer,
+      1n
+    );
+
+    const withdrawPosition = (
+      holder: Keypair,
+      holderPositionAccount: PublicKey,
+      shares: bigint
+    ) =>
+      program.methods
+        .withdrawPosition(bn(shares))
+        .accounts({
+          ...pool,
+          position,
+          positionMint: positionMint.publicKey,
+          positionTokenAccount: holderPositionAccount,
+          tokenAAccount: getAssociatedTokenAddressSync(
+            pool.tokenAMint,
+            holder.publicKey
+          ),
+          tokenBAccount: getAssociatedTokenAddressSync(
+            pool.tokenBMint,
+            holder.publicKey
+          ),
+          signer: holder.publicKey,
+        })
+        .signers([holder])
+        .rpc();
+
+    await expectError(
+      withdrawPosition(payer, positionTokenAccount, 1n),
+      "NotPositionHolder"
+    );
+
+    const shares = BigInt(opened.shares.toString());
+    await expectError(
+      withdrawPosition(bob, bobPositionAccount, shares + 1n),
+      "InsufficientShares"
+    );
+
+    await withdrawPosition(bob, bobPositionAccount, shares / 2n);
+    const half = await program.account.position.fetch(position);
+    assert.equal(half.shares.toString(), (shares - shares / 2n).toString());
+    assert.equal(half.feesAccruedA.toString(), fees.feesA.toString());
+    assert.equal(half.feesAccruedB.toString(), fees.feesB.toString());
+
+    const bobTokenA = getAssociatedTokenAddressSync(
+      pool.tokenAMint,
+      bob.publicKey
+    );
+    assert.equal(await balance(bobTokenA), BigInt(half.withdrawnA.toString()));
+
+    // emptying the position burns the NFT and closes the position
+    await withdrawPosition(bob, bobPositionAccount, shares - shares / 2n);
+    assert.isNull(await connection.getAccountInfo(position));
+    assert.isNull(await connection.getAccountInfo(bobPositionAccount));
+    assert.equal(
+      (await getMint(connection, positionMint.publicKey)).supply,
+      0n
+    );
+    assert.equal(
+      (await program.account.amm.fetch(pool.amm)).lpSupply.toString(),
+      lpSupply.toString()
+    );
+  });
+
+  it("Splits LP withdrawals into principal and earned fees", async () => {
+    const pool = await createPool(6, 1_000_000_000n);
+    // only deposits made with the checkpoint are tracked
+    const tracked = await withFeeCheckpoint(pool);
+    await deposit(tracked, 100_000_000n, 400_000_000n);
+    await setFeeConfig(pool, 30, 30, false);
+
+    const quoteEarnedFees = (accounts: Pool) =>
+      program.methods
+        .quoteEarnedFees()
+        .accounts({ ...accounts, owner: accounts.signer })
+        .view();
+    const fetchCheckpoint = (accounts: Pool) =>
+      program.account.lpFeeCheckpoint.fetch(accounts.lpFeeCheckpoint);
+
+    // the checkpoint counts the LP tokens minted through it
+    let checkpoint = await fetchCheckpoint(tracked);
+    assert.equal(
+      checkpoint.lpBalance.toString(),
+      (await balance(pool.tokenLpAccount)).toString()
+    );
+
+    // nothing is earned before the first trade
+    let fees = await quoteEarnedFees(tracked);
+    assert.equal(fees.feesA.toString(), "0");
+    assert.equal(fees.feesB.toString(), "0");
+
+    // 0.3% of each input stays in the pool, all of it earned by the wallet
+    await swap(pool, 10_000_000n, true);
+    await swap(pool, 40_000_000n, false);
+    fees = await quoteEarnedFees(tracked);
+    assert.closeTo(fees.feesA.toNumber(), 30_000, 1);
+    assert.closeTo(fees.feesB.toNumber(), 120_000, 1);
+
+    // withdrawing settles the fees and burns from the tracked balance
+    const half = (await balance(pool.tokenLpAccount)) / 2n;
+    await withdraw(tracked, half);
+    checkpoint = await fetchCheckpoint(tracked);
+    assert.equal(checkpoint.earnedA.toString(), fees.feesA.toString());
+    assert.equal(checkpoint.earnedB.toString(), fees.feesB.toString());
+    assert.equal(
+      checkpoint.lpBalance.toString(),
+      (await balance(pool.tokenLpAccount)).toString()
+    );
+
+    // a newcomer only earns on trades after their deposit
+    const user = Keypair.generate();
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: payer.publicKey,
+          toPubkey: user.publicKey,
+          lamports: LAMPORTS_PER_SOL,
+        })
+      )
+    );
+    const userAccounts = {
+      ...(await withFeeCheckpoint(pool, user)),
+      tokenAAccount: await createAssociatedTokenAccount(
+        connection,
+        payer,
+        pool.tokenAMint,
+        user.publicKey
+      ),
+      tokenBAccount: getAssociatedTokenAddressSync(
+        pool.tokenBMint,
+        user.publicKey
+      ),
+      tokenLpAccount: getAssociatedTokenAddressSync(
+        pool.lpMint,
+        user.publicKey
+      ),
+      signer: user.publicKey,
+    };
+    await transfer(
+      connection,
+      payer,
+      pool.tokenAAccount,
+      userAccounts.tokenAAccount,
+      payer,
+      10_000_000n
+    );
+    await program.methods
+      .depositSingle(bn(10_000_000n), true, bn(1n))
+      .accounts(userAccounts)
+      .signers([user])
+      .rpc();
+    const newcomerLp = await balance(userAccounts.tokenLpAccount);
+    assert.equal(
+      (await fetchCheckpoint(userAccounts)).lpBalance.toString(),
+      newcomerLp.toString()
+    );
+
+    // the fee of the deposit's own swap leg went to the existing LPs
+    const newcomerBefore = await quoteEarnedFees(userAccounts);
+    assert.equal(newcomerBefore.feesA.toString(), "0");
+    assert.equal(newcomerBefore.feesB.toString(), "0");
+    const payerBefore = await quoteEarnedFees(tracked);
+    assert.isTrue(payerBefore.feesA.gt(fees.feesA));
+
+    // the next fee is shared between both wallets
+    await swap(pool, 10_000_000n, true);
+    const payerFees = await quoteEarnedFees(tracked);
+    const newcomerFees = await quoteEarnedFees(userAccounts);
+    assert.isTrue(newcomerFees.feesA.gtn(0));
+    assert.closeTo(
+      payerFees.feesA.sub(payerBefore.feesA).add(newcomerFees.feesA).toNumber(),
+      30_000,
+      2
+    );
+
+    // LP tokens moved between wallets earn for neither checkpoint: the
+    // payer's tracked balance is capped at what it still holds and the
+    // newcomer only earns on the LP tokens minted through its own
+    await transfer(
+      connection,
+      payer,
+      pool.tokenLpAccount,
+      userAccounts.tokenLpAccount,
+      payer,
+      await balance(pool.tokenLpAccount)
+    );
+    await swap(pool, 10_000_000n, true);
+    const payerAfter = await quoteEarnedFees(tracked);
+    const newcomerAfter = await quoteEarnedFees(userAccounts);
+    assert.equal(payerAfter.feesA.toString(), payerFees.feesA.toString());
+    const lpSupply = (await getMint(connection, pool.lpMint)).supply;
+    assert.closeTo(
+      newcomerAfter.feesA.sub(newcomerFees.feesA).toNumber(),
+      Number((30_000n * newcomerLp) / lpSupply),
+      2
+    );
+  });
+
+  it("Trades through an integrator program with CPI", async () => {
+    const router = anchor.workspace.SwapRouter as Program<SwapRouter>;
+    const pool = await createPool(6, 1_000_000_000n);
+    await deposit(pool, 100_000_000n, 400_000_000n);
+
+    // deposits and withdrawals take the LP side of the pool accounts too,
+    // the fee checkpoint being optional - 10% of the pool is 20 of 200 LP
+    // tokens
+    const routedDeposit = (minLpTokens: bigint) =>
+      router.methods
+        .depositWithMinLp(bn(10_000_000n), bn(40_000_000n), bn(minLpTokens))
+        .accounts({ ...pool, ammProgram: program.programId })
+        .rpc();
+    await expectError(
+      routedDeposit(20_000_001n),
+      "Output is below the minimum"
+    );
+    const lpBefore = await balance(pool.tokenLpAccount);
+    await routedDeposit(20_000_000n);
+    assert.equal((await balance(pool.tokenLpAccount)) - lpBefore, 20_000_000n);
+
+    const routedWithdraw = (minAmountA: bigint, minAmountB: bigint) =>
+      router.methods
+        .withdrawWithMinOut(bn(20_000_000n), bn(minAmountA), bn(minAmountB))
+        .accounts({ ...pool, ammProgram: program.programId })
+        .rpc();
+    await expectError(
+      routedWithdraw(10_000_000n, 40_000_001n),
+      "Output is below the minimum"
+    );
+    await routedWithdraw(10_000_000n, 40_000_000n);
+    assert.equal(await balance(pool.tokenLpAccount), lpBefore);
+    assert.equal(await balance(pool.reserveA), 100_000_000n);
+    assert.equal(await balance(pool.reserveB), 400_000_000n);
+
+    const routedSwap = (quantity: bigint, isA: boolean, minAmountOut: bigint) =>
+      router.methods
+        .swapWithMinOut(bn(quantity), isA, bn(minAmountOut))
+        .accounts({ ...pool, ammProgram: program.programId })
+        .rpc();
+
+    // the router adds a minimum output on top of the AMM's swap
+    const expected = expectedSwapOutput(
+      10_000_000n,
+      100_000_000n,
+      400_000_000n
+    );
+    await expectError(
+      routedSwap(10_000_000n, true, expected + 1n),
+      "Output is below the minimum"
+    );
+
+    const before = await balance(pool.tokenBAccount);
+    await routedSwap(10_000_000n, true, expected);
+    assert.equal((await balance(pool.tokenBAccount)) - before, expected);
+    assert.equal(await balance(pool.reserveA), 110_000_000n);
+    assert.equal(await balance(pool.reserveB), 400_000_000n - expected);
   });
 });